@@ -1,3 +1,4 @@
+use crate::dispatcher::Dispatcher;
 use crate::mcp;
 use crate::mcp::server;
 use crate::transport::Channel;
@@ -9,15 +10,23 @@ use std::io;
 
 pub struct Connection {
     channel: Channel,
+    dispatcher: Dispatcher,
+    cancellation: Cancellation,
 }
 
 impl Connection {
-    pub fn new(channel: Channel) -> Self {
-        Self { channel }
+    pub fn new(channel: Channel, dispatcher: Dispatcher, cancellation: Cancellation) -> Self {
+        Self {
+            channel,
+            dispatcher,
+            cancellation,
+        }
     }
 
     pub(crate) async fn next<T: DeserializeOwned>(&mut self) -> io::Result<server::Message<T>> {
         let Some(bytes) = self.channel.next().await else {
+            self.cancellation.disarm();
+
             return Err(io::Error::new(
                 io::ErrorKind::ConnectionReset,
                 "stream was closed by peer",
@@ -25,7 +34,15 @@ impl Connection {
         };
 
         match server::Message::deserialize(&bytes) {
-            Ok(message) => Ok(message),
+            Ok(message) => {
+                self.dispatcher.dispatch(&message);
+
+                if let server::Message::Response(_) | server::Message::Error(_) = &message {
+                    self.cancellation.disarm();
+                }
+
+                Ok(message)
+            }
             Err(error) => Err(io::Error::new(
                 io::ErrorKind::InvalidData,
                 format!("invalid message: {error}"),
@@ -35,9 +52,38 @@ impl Connection {
 
     pub(crate) async fn response<T: DeserializeOwned>(mut self) -> io::Result<mcp::Response<T>> {
         loop {
-            if let server::Message::Response(response) = self.next().await? {
-                return Ok(response);
+            match self.next().await? {
+                server::Message::Response(response) => return Ok(response),
+                server::Message::Error(error) => return Err(error.into()),
+                _ => {}
             }
         }
     }
 }
+
+/// Notifies the server when a request is dropped before receiving a response.
+pub struct Cancellation {
+    id: mcp::Id,
+    dispatcher: Option<Dispatcher>,
+}
+
+impl Cancellation {
+    pub fn new(id: mcp::Id, dispatcher: Dispatcher) -> Self {
+        Self {
+            id,
+            dispatcher: Some(dispatcher),
+        }
+    }
+
+    pub fn disarm(&mut self) {
+        self.dispatcher = None;
+    }
+}
+
+impl Drop for Cancellation {
+    fn drop(&mut self) {
+        if let Some(dispatcher) = self.dispatcher.take() {
+            dispatcher.cancel(self.id);
+        }
+    }
+}
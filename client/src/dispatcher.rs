@@ -0,0 +1,188 @@
+use crate::handler::Handlers;
+use crate::mcp::{self, client, server};
+use crate::transport::{Channel, Transport};
+
+use futures::StreamExt;
+use futures::channel::mpsc;
+use futures::future::{AbortHandle, Abortable};
+use tokio::runtime;
+use tokio::task;
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+#[derive(Clone)]
+pub(crate) struct Dispatcher {
+    transport: Arc<dyn Transport + Send + Sync>,
+    handlers: Arc<Handlers>,
+    listeners: Arc<Mutex<Vec<mpsc::UnboundedSender<server::Notification>>>>,
+    requests: Arc<Mutex<BTreeMap<mcp::Id, AbortHandle>>>,
+    tools: Arc<Mutex<Cache<Vec<server::Tool>>>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Cache<T> {
+    value: Option<T>,
+    generation: u64,
+}
+
+impl<T> Default for Cache<T> {
+    fn default() -> Self {
+        Self {
+            value: None,
+            generation: 0,
+        }
+    }
+}
+
+impl Dispatcher {
+    pub fn new(transport: Arc<dyn Transport + Send + Sync>, handlers: Handlers) -> Self {
+        Self {
+            transport,
+            handlers: Arc::new(handlers),
+            listeners: Arc::default(),
+            requests: Arc::default(),
+            tools: Arc::default(),
+        }
+    }
+
+    pub fn subscribe(&self) -> mpsc::UnboundedReceiver<server::Notification> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.listeners().push(sender);
+
+        receiver
+    }
+
+    pub fn dispatch<T>(&self, message: &server::Message<T>) {
+        match message {
+            server::Message::Notification(notification) => {
+                if let server::Notification::ToolsListChanged = &notification.payload {
+                    self.invalidate_tools();
+                }
+
+                if let server::Notification::Cancelled { params: cancelled } = &notification.payload
+                    && let Some(request) = self.requests().remove(&cancelled.request_id)
+                {
+                    request.abort();
+                }
+
+                self.listeners()
+                    .retain(|sender| sender.unbounded_send(notification.payload.clone()).is_ok());
+            }
+            server::Message::Request(request) => {
+                let (handle, registration) = AbortHandle::new_pair();
+                let _ = self.requests().insert(request.id, handle);
+
+                drop(task::spawn(Abortable::new(
+                    self.clone().respond(request.id, request.payload.clone()),
+                    registration,
+                )));
+            }
+            server::Message::Response(_) | server::Message::Error(_) => {}
+        }
+    }
+
+    pub async fn run(self, mut channel: Channel) {
+        while let Some(bytes) = channel.next().await {
+            match server::Message::<mcp::Ignored>::deserialize(&bytes) {
+                Ok(message) => self.dispatch(&message),
+                Err(error) => log::warn!("{error}"),
+            }
+        }
+    }
+
+    /// Returns the cached tools, if still valid, and the current
+    /// generation of the cache.
+    pub fn cached_tools(&self) -> (Option<Vec<server::Tool>>, u64) {
+        let tools = self.tools();
+
+        (tools.value.clone(), tools.generation)
+    }
+
+    pub fn invalidate_tools(&self) {
+        let mut tools = self.tools();
+        tools.value = None;
+        tools.generation += 1;
+    }
+
+    /// Caches the given tools, unless the cache was invalidated after
+    /// `generation`.
+    pub fn cache_tools(&self, tools: Vec<server::Tool>, generation: u64) {
+        let mut cache = self.tools();
+
+        if cache.generation == generation {
+            cache.value = Some(tools);
+        }
+    }
+
+    /// Returns the output schema of a cached tool.
+    pub fn output_schema(&self, tool: &str) -> Option<mcp::Schema> {
+        self.tools()
+            .value
+            .as_ref()?
+            .iter()
+            .find(|candidate| candidate.name == tool)?
+            .output_schema
+            .clone()
+    }
+
+    pub fn cancel(&self, id: mcp::Id) {
+        let Ok(runtime) = runtime::Handle::try_current() else {
+            return;
+        };
+
+        let transport = self.transport.clone();
+
+        drop(runtime.spawn(async move {
+            let notification = mcp::Notification::new(client::Notification::from(mcp::Cancelled {
+                request_id: id,
+                reason: None,
+            }));
+
+            let result = match notification.serialize() {
+                Ok(bytes) => transport.send(bytes).await.map(|_| ()),
+                Err(error) => Err(error.into()),
+            };
+
+            if let Err(error) = result {
+                log::warn!("{error}");
+            }
+        }));
+    }
+
+    async fn respond(self, id: mcp::Id, request: server::Request) {
+        let response = self.handlers.handle(request).await;
+
+        if self.requests().remove(&id).is_none() {
+            return;
+        }
+
+        let bytes = match response {
+            Ok(response) => mcp::Response::new(id, response).serialize(),
+            Err(error) => mcp::Error::new(Some(id), error).serialize(),
+        };
+
+        let result = match bytes {
+            Ok(bytes) => self.transport.send(bytes).await.map(|_| ()),
+            Err(error) => Err(error.into()),
+        };
+
+        if let Err(error) = result {
+            log::error!("{error}");
+        }
+    }
+
+    fn tools(&self) -> MutexGuard<'_, Cache<Vec<server::Tool>>> {
+        self.tools.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn requests(&self) -> MutexGuard<'_, BTreeMap<mcp::Id, AbortHandle>> {
+        self.requests.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn listeners(&self) -> MutexGuard<'_, Vec<mpsc::UnboundedSender<server::Notification>>> {
+        self.listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,114 @@
+use crate::Roots;
+use crate::mcp::client::{self, Capabilities, capabilities, elicitation, response, sampling};
+use crate::mcp::server::request::{CreateElicitation, CreateMessage};
+use crate::mcp::{ErrorKind, server};
+
+use futures::FutureExt;
+use futures::future::BoxFuture;
+
+use std::io;
+
+pub trait Sampling: Send + Sync {
+    fn create_message(
+        &self,
+        request: CreateMessage,
+    ) -> BoxFuture<'static, io::Result<sampling::Response>>;
+}
+
+impl<T, F> Sampling for T
+where
+    T: Fn(CreateMessage) -> F + Send + Sync,
+    F: Future<Output = io::Result<sampling::Response>> + Send + 'static,
+{
+    fn create_message(
+        &self,
+        request: CreateMessage,
+    ) -> BoxFuture<'static, io::Result<sampling::Response>> {
+        self(request).boxed()
+    }
+}
+
+pub trait Elicitation: Send + Sync {
+    fn elicit(
+        &self,
+        request: CreateElicitation,
+    ) -> BoxFuture<'static, io::Result<elicitation::Response>>;
+}
+
+impl<T, F> Elicitation for T
+where
+    T: Fn(CreateElicitation) -> F + Send + Sync,
+    F: Future<Output = io::Result<elicitation::Response>> + Send + 'static,
+{
+    fn elicit(
+        &self,
+        request: CreateElicitation,
+    ) -> BoxFuture<'static, io::Result<elicitation::Response>> {
+        self(request).boxed()
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Handlers {
+    pub sampling: Option<Box<dyn Sampling>>,
+    pub elicitation: Option<Box<dyn Elicitation>>,
+    pub roots: Option<Roots>,
+}
+
+impl Handlers {
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            roots: self
+                .roots
+                .as_ref()
+                .map(|_| capabilities::Roots { list_changed: true }),
+            sampling: self
+                .sampling
+                .as_ref()
+                .map(|_| capabilities::Sampling::default()),
+            elicitation: self
+                .elicitation
+                .as_ref()
+                .map(|_| capabilities::Elicitation::default()),
+        }
+    }
+
+    pub async fn handle(&self, request: server::Request) -> Result<client::Response, ErrorKind> {
+        match request {
+            server::Request::Ping => Ok(client::Response::Ping {}),
+            server::Request::SamplingCreateMessage { params: create } => {
+                let Some(sampling) = &self.sampling else {
+                    return Err(ErrorKind::method_not_found(
+                        "sampling/createMessage".to_owned(),
+                    ));
+                };
+
+                sampling
+                    .create_message(create)
+                    .await
+                    .map(client::Response::from)
+                    .map_err(|error| ErrorKind::internal_error(error.to_string()))
+            }
+            server::Request::ElicitationCreate { params: create } => {
+                let Some(elicitation) = &self.elicitation else {
+                    return Err(ErrorKind::method_not_found("elicitation/create".to_owned()));
+                };
+
+                elicitation
+                    .elicit(create)
+                    .await
+                    .map(client::Response::from)
+                    .map_err(|error| ErrorKind::internal_error(error.to_string()))
+            }
+            server::Request::RootsList => {
+                let Some(roots) = &self.roots else {
+                    return Err(ErrorKind::method_not_found("roots/list".to_owned()));
+                };
+
+                Ok(client::Response::from(response::RootsList {
+                    roots: roots.list(),
+                }))
+            }
+        }
+    }
+}
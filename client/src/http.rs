@@ -1,71 +1,63 @@
-use crate::mcp::Bytes;
-use crate::transport::{Channel, Transport};
+mod sse;
+
+use crate::mcp::{Bytes, Version};
+use crate::transport::{Channel, SessionExpired, Transport};
 
 use futures::SinkExt;
 use futures::channel::mpsc;
 use futures::future::{BoxFuture, FutureExt};
 use reqwest::header;
-use reqwest::{Client, Error, IntoUrl, Response, Url};
+use reqwest::{Client, Error, IntoUrl, RequestBuilder, Response, StatusCode, Url};
 use tokio::task;
+use tokio::time;
 
 use std::io;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
 
 pub struct Http {
-    client: Client,
-    address: Url,
+    endpoint: Endpoint,
 }
 
 impl Http {
     pub fn new(address: impl IntoUrl) -> io::Result<Self> {
         Ok(Self {
-            client: Client::new(),
-            address: address.into_url().map_err(to_error)?,
+            endpoint: Endpoint {
+                client: Client::new(),
+                address: address.into_url().map_err(to_error)?,
+                session: Session::default(),
+            },
         })
     }
 }
 
 impl Transport for Http {
     fn listen(&self) -> BoxFuture<'static, io::Result<Channel>> {
-        let client = self.client.clone();
-        let address = self.address.clone();
+        let endpoint = self.endpoint.clone();
 
         async move {
-            let response = client
-                .get(address)
-                .header(header::ACCEPT, "text/event-stream")
-                .send()
-                .await
-                .map_err(to_error)?
-                .error_for_status()
-                .map_err(to_error)?;
-
-            let (sender, receiver) = mpsc::channel(10);
+            let response = endpoint.get(None).await?;
 
-            drop(task::spawn(async move {
-                if let Err(error) = read_stream(sender, response).await {
-                    log::error!("{error}");
-                }
-            }));
-
-            Ok(receiver)
+            Ok(endpoint.stream(response, true))
         }
         .boxed()
     }
 
+    fn set_protocol_version(&self, version: Version) {
+        *self.endpoint.session.version() = Some(version);
+    }
+
     fn send(&self, bytes: Bytes) -> BoxFuture<'static, io::Result<Channel>> {
-        let client = self.client.clone();
-        let address = self.address.clone();
+        let endpoint = self.endpoint.clone();
 
         async move {
-            let response = client
-                .post(address)
-                .header(header::ACCEPT, "application/json, text/event-stream")
-                .body(bytes)
-                .send()
-                .await
-                .map_err(to_error)?
-                .error_for_status()
-                .map_err(to_error)?;
+            let response = endpoint.post(bytes).await?;
+
+            if response.status() == StatusCode::ACCEPTED {
+                let (_sender, receiver) = mpsc::channel(1);
+
+                return Ok(receiver);
+            }
 
             match response
                 .headers()
@@ -80,17 +72,7 @@ impl Transport for Http {
 
                     Ok(receiver)
                 }
-                Some("text/event-stream") => {
-                    let (sender, receiver) = mpsc::channel(10);
-
-                    drop(task::spawn(async move {
-                        if let Err(error) = read_stream(sender, response).await {
-                            log::error!("{error}");
-                        }
-                    }));
-
-                    Ok(receiver)
-                }
+                Some("text/event-stream") => Ok(endpoint.stream(response, false)),
                 content_type => Err(io::Error::new(
                     io::ErrorKind::Unsupported,
                     format!("invalid server content-type: {content_type:?}"),
@@ -101,24 +83,189 @@ impl Transport for Http {
     }
 }
 
-async fn read_stream(mut sender: mpsc::Sender<Bytes>, mut response: Response) -> Result<(), Error> {
-    static PREFIX: usize = b"data:".len();
+#[derive(Debug, Clone)]
+struct Endpoint {
+    client: Client,
+    address: Url,
+    session: Session,
+}
 
-    let mut last_event = Vec::new();
+impl Endpoint {
+    async fn get(&self, last_event: Option<&str>) -> io::Result<Response> {
+        let request = self
+            .client
+            .get(self.address.clone())
+            .header(header::ACCEPT, "text/event-stream");
 
-    while let Some(chunk) = response.chunk().await? {
-        for chunk in chunk.split(|byte| *byte == 0xA) {
-            if chunk.is_empty() && !last_event.is_empty() {
-                if last_event.len() > PREFIX {
-                    let _ = sender
-                        .send(Bytes::copy_from_slice(&last_event[PREFIX..]))
-                        .await;
+        let request = match last_event {
+            Some(id) => request.header(LAST_EVENT_ID, id),
+            None => request,
+        };
+
+        self.session.execute(request).await
+    }
+
+    async fn post(&self, bytes: Bytes) -> io::Result<Response> {
+        self.session
+            .execute(
+                self.client
+                    .post(self.address.clone())
+                    .header(header::ACCEPT, "application/json, text/event-stream")
+                    .body(bytes),
+            )
+            .await
+    }
+
+    fn stream(&self, response: Response, is_outbound: bool) -> Channel {
+        let (sender, receiver) = mpsc::channel(10);
+
+        drop(task::spawn(self.clone().read(
+            sender,
+            response,
+            is_outbound,
+        )));
+
+        receiver
+    }
+
+    /// Reads the events of a stream, resuming it from its last event
+    /// whenever the connection drops.
+    async fn read(
+        self,
+        mut sender: mpsc::Sender<Bytes>,
+        mut response: Response,
+        is_outbound: bool,
+    ) {
+        let mut decoder = sse::Decoder::new();
+
+        loop {
+            let result = read_stream(&mut sender, response, &mut decoder).await;
+
+            decoder.reset();
+
+            if sender.is_closed() {
+                return;
+            }
+
+            match result {
+                // Request streams end once they are finished, but the outbound
+                // stream may be closed at any time by proxies
+                Ok(()) if !is_outbound => return,
+                Ok(()) => {
+                    log::debug!("Stream ended");
+                }
+                // Only the outbound stream can be resumed without an event id
+                Err(error) if !is_outbound && decoder.last_event_id().is_none() => {
+                    log::error!("{error}");
+                    return;
+                }
+                Err(error) => {
+                    log::debug!("Stream disconnected: {error}");
                 }
+            }
+
+            let Some(resumed) = self
+                .reconnect(
+                    decoder.last_event_id(),
+                    decoder.retry().unwrap_or(RECONNECT_DELAY),
+                )
+                .await
+            else {
+                return;
+            };
+
+            response = resumed;
+        }
+    }
+
+    async fn reconnect(&self, last_event: Option<&str>, delay: Duration) -> Option<Response> {
+        for _ in 0..RECONNECT_ATTEMPTS {
+            time::sleep(delay).await;
+
+            match self.get(last_event).await {
+                Ok(response) => return Some(response),
+                Err(error) if SessionExpired::is(&error) => return None,
+                Err(error) => log::debug!("{error}"),
+            }
+        }
+
+        log::error!("Could not resume stream after {RECONNECT_ATTEMPTS} attempts");
+
+        None
+    }
+}
+
+/// The session id assigned by the server and the negotiated protocol
+/// version, if any.
+#[derive(Debug, Clone, Default)]
+struct Session {
+    id: Arc<Mutex<Option<String>>>,
+    version: Arc<Mutex<Option<Version>>>,
+}
+
+impl Session {
+    /// Sends a request within the session, keeping track of the
+    /// session id assigned by the server.
+    async fn execute(&self, request: RequestBuilder) -> io::Result<Response> {
+        let id = self.id().clone();
+
+        let request = match &id {
+            Some(id) => request.header(SESSION_ID, id),
+            None => request,
+        };
+
+        let request = match *self.version() {
+            Some(version) => request.header(PROTOCOL_VERSION, version.as_str()),
+            None => request,
+        };
 
-                last_event.clear();
+        let response = request.send().await.map_err(to_error)?;
+
+        if response.status() == StatusCode::NOT_FOUND && id.is_some() {
+            let mut current = self.id();
+
+            if *current == id {
+                *current = None;
             }
 
-            last_event.extend_from_slice(chunk);
+            return Err(SessionExpired.into());
+        }
+
+        let response = response.error_for_status().map_err(to_error)?;
+
+        if let Some(id) = response
+            .headers()
+            .get(SESSION_ID)
+            .and_then(|id| id.to_str().ok())
+        {
+            *self.id() = Some(id.to_owned());
+        }
+
+        Ok(response)
+    }
+
+    fn id(&self) -> MutexGuard<'_, Option<String>> {
+        self.id.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn version(&self) -> MutexGuard<'_, Option<Version>> {
+        self.version.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Reads the messages of a stream until it ends.
+async fn read_stream(
+    sender: &mut mpsc::Sender<Bytes>,
+    mut response: Response,
+    decoder: &mut sse::Decoder,
+) -> Result<(), Error> {
+    while let Some(chunk) = response.chunk().await? {
+        decoder.push(&chunk);
+
+        while let Some(event) = decoder.next() {
+            if event.is_message() && sender.send(event.data).await.is_err() {
+                return Ok(());
+            }
         }
     }
 
@@ -136,3 +283,10 @@ fn to_error(error: Error) -> io::Error {
 
     io::Error::other(error.to_string())
 }
+
+const SESSION_ID: &str = "mcp-session-id";
+const LAST_EVENT_ID: &str = "last-event-id";
+const PROTOCOL_VERSION: &str = "mcp-protocol-version";
+
+const RECONNECT_ATTEMPTS: usize = 5;
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
@@ -0,0 +1,316 @@
+//! An incremental decoder of `text/event-stream` bodies, following the
+//! [HTML Living Standard](https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation).
+use crate::mcp::Bytes;
+
+use std::mem;
+use std::time::Duration;
+
+/// An event of a stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub kind: String,
+    pub data: Bytes,
+    pub id: Option<String>,
+}
+
+impl Event {
+    pub fn is_message(&self) -> bool {
+        self.kind == "message"
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Decoder {
+    buffer: Vec<u8>,
+    kind: String,
+    data: Vec<u8>,
+    id: Option<String>,
+    last_event_id: String,
+    retry: Option<Duration>,
+    is_started: bool,
+    skip_line_feed: bool,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a chunk of the stream to the decoder.
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Decodes the next complete event fed to the decoder, if any.
+    pub fn next(&mut self) -> Option<Event> {
+        while let Some(line) = self.line() {
+            if let Some(event) = self.process(&line) {
+                return Some(event);
+            }
+        }
+
+        None
+    }
+
+    /// Returns the id of the last event, which must be sent to the server
+    /// when reconnecting.
+    pub fn last_event_id(&self) -> Option<&str> {
+        (!self.last_event_id.is_empty()).then_some(self.last_event_id.as_str())
+    }
+
+    /// Returns the reconnection time requested by the server, if any.
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+
+    /// Discards any incomplete event, as when the stream ends.
+    ///
+    /// The id of the last dispatched event and the reconnection time
+    /// are kept.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.kind.clear();
+        self.data.clear();
+        self.id = None;
+        self.is_started = false;
+        self.skip_line_feed = false;
+    }
+
+    fn line(&mut self) -> Option<Vec<u8>> {
+        if !self.is_started {
+            if self.buffer.len() < BOM.len() && BOM.starts_with(&self.buffer) {
+                return None;
+            }
+
+            if self.buffer.starts_with(BOM) {
+                let _ = self.buffer.drain(..BOM.len());
+            }
+
+            self.is_started = true;
+        }
+
+        // A carriage return may be followed by a line feed in the next chunk
+        if self.skip_line_feed && !self.buffer.is_empty() {
+            if self.buffer[0] == b'\n' {
+                let _ = self.buffer.remove(0);
+            }
+
+            self.skip_line_feed = false;
+        }
+
+        let end = self
+            .buffer
+            .iter()
+            .position(|byte| *byte == b'\r' || *byte == b'\n')?;
+
+        self.skip_line_feed = self.buffer[end] == b'\r';
+
+        let mut line: Vec<u8> = self.buffer.drain(..=end).collect();
+        let _ = line.pop();
+
+        Some(line)
+    }
+
+    fn process(&mut self, line: &[u8]) -> Option<Event> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+
+        if line.starts_with(b":") {
+            return None;
+        }
+
+        let (field, value) = match line.iter().position(|byte| *byte == b':') {
+            Some(colon) => {
+                let value = &line[colon + 1..];
+
+                (&line[..colon], value.strip_prefix(b" ").unwrap_or(value))
+            }
+            None => (line, &[][..]),
+        };
+
+        match field {
+            b"event" => {
+                self.kind = String::from_utf8_lossy(value).into_owned();
+            }
+            b"data" => {
+                self.data.extend_from_slice(value);
+                self.data.push(b'\n');
+            }
+            // The id only takes effect once its event is dispatched
+            b"id" if !value.contains(&0) => {
+                self.id = Some(String::from_utf8_lossy(value).into_owned());
+            }
+            b"retry" if !value.is_empty() && value.iter().all(u8::is_ascii_digit) => {
+                if let Some(milliseconds) = std::str::from_utf8(value)
+                    .ok()
+                    .and_then(|value| value.parse().ok())
+                {
+                    self.retry = Some(Duration::from_millis(milliseconds));
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<Event> {
+        let kind = mem::take(&mut self.kind);
+
+        if let Some(id) = self.id.take() {
+            self.last_event_id = id;
+        }
+
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let mut data = mem::take(&mut self.data);
+        let _ = data.pop();
+
+        Some(Event {
+            kind: if kind.is_empty() {
+                "message".to_owned()
+            } else {
+                kind
+            },
+            data: Bytes::from(data),
+            id: self.last_event_id().map(str::to_owned),
+        })
+    }
+}
+
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(chunks: &[&[u8]]) -> (Vec<Event>, Decoder) {
+        let mut decoder = Decoder::new();
+        let mut events = Vec::new();
+
+        for chunk in chunks {
+            decoder.push(chunk);
+
+            while let Some(event) = decoder.next() {
+                events.push(event);
+            }
+        }
+
+        (events, decoder)
+    }
+
+    fn data(events: &[Event]) -> Vec<&[u8]> {
+        events.iter().map(|event| event.data.as_ref()).collect()
+    }
+
+    #[test]
+    fn line_endings() {
+        let (events, _) = decode(&[b"data: a\r\n\r\ndata: b\r\rdata: c\n\n"]);
+
+        assert_eq!(data(&events), [&b"a"[..], b"b", b"c"]);
+    }
+
+    #[test]
+    fn carriage_return_split_across_chunks() {
+        let (events, _) = decode(&[b"data: a\r", b"\n\r", b"\ndata: b\r", b"\r"]);
+
+        assert_eq!(data(&events), [&b"a"[..], b"b"]);
+    }
+
+    #[test]
+    fn multiline_data() {
+        let (events, _) = decode(&[b"data: a\ndata:b\ndata\n\n"]);
+
+        assert_eq!(data(&events), [&b"a\nb\n"[..]]);
+    }
+
+    #[test]
+    fn comments() {
+        let (events, _) = decode(&[b": ping\n\n:\ndata: a\n: more\n\n"]);
+
+        assert_eq!(data(&events), [&b"a"[..]]);
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        // Only a leading BOM is stripped
+        let (events, _) = decode(&[b"\xEF\xBB", b"\xBFdata: a\n\n\xEF\xBB\xBFdata: b\n\n"]);
+
+        assert_eq!(data(&events), [&b"a"[..]]);
+    }
+
+    #[test]
+    fn event_kind() {
+        let (events, _) = decode(&[b"event: ping\ndata: a\n\ndata: b\n\n"]);
+
+        assert!(!events[0].is_message());
+        assert!(events[1].is_message());
+    }
+
+    #[test]
+    fn event_id() {
+        let (events, decoder) = decode(&[b"id: 1\n\nid: 2\ndata: a\n\ndata: b\n\nid\n\n"]);
+
+        assert_eq!(events[0].id.as_deref(), Some("2"));
+        assert_eq!(events[1].id.as_deref(), Some("2"));
+        assert_eq!(decoder.last_event_id(), None);
+
+        let (_, decoder) = decode(&[b"id: 3\n\nid: 4\0\n\n"]);
+
+        assert_eq!(decoder.last_event_id(), Some("3"));
+    }
+
+    #[test]
+    fn id_without_data_is_kept() {
+        let (events, decoder) = decode(&[b"id: 7\n\n"]);
+
+        assert!(events.is_empty());
+        assert_eq!(decoder.last_event_id(), Some("7"));
+    }
+
+    #[test]
+    fn id_of_interrupted_event_is_discarded() {
+        let mut decoder = Decoder::new();
+        decoder.push(b"id: 4\n\n");
+        assert_eq!(decoder.next(), None);
+
+        decoder.push(b"id: 5\n");
+        assert_eq!(decoder.next(), None);
+
+        decoder.reset();
+        assert_eq!(decoder.last_event_id(), Some("4"));
+
+        decoder.push(b"data: x\n\n");
+
+        let event = decoder.next().expect("event must be decoded");
+
+        assert_eq!(event.data.as_ref(), b"x");
+        assert_eq!(event.id.as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn retry() {
+        let (_, decoder) = decode(&[b"retry: 1500\n\nretry: soon\n\n"]);
+
+        assert_eq!(decoder.retry(), Some(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn reset() {
+        let mut decoder = Decoder::new();
+        decoder.push(b"id: 1\nretry: 10\n\ndata: incomplete\n");
+        assert_eq!(decoder.next(), None);
+
+        decoder.reset();
+        decoder.push(b"\ndata: a\n\n");
+
+        let event = decoder.next().expect("event must be decoded");
+
+        assert_eq!(event.data.as_ref(), b"a");
+        assert_eq!(decoder.last_event_id(), Some("1"));
+        assert_eq!(decoder.retry(), Some(Duration::from_millis(10)));
+    }
+}
@@ -1,26 +1,44 @@
 pub use techne_mcp as mcp;
 
+pub mod handler;
 pub mod transport;
 
 mod connection;
+mod dispatcher;
 #[cfg(feature = "http")]
 mod http;
+mod roots;
 mod stdio;
+mod subscription;
 
 #[cfg(feature = "http")]
 pub use http::Http;
+pub use roots::Roots;
 pub use stdio::Stdio;
+pub use subscription::Subscription;
 pub use transport::Transport;
 
-use connection::Connection;
+use connection::{Cancellation, Connection};
+use dispatcher::Dispatcher;
+use handler::Handlers;
+use transport::SessionExpired;
 
 use crate::mcp::client::request;
-use crate::mcp::client::{Capabilities, Notification, Request, Response};
+use crate::mcp::client::{Notification, Request};
+use crate::mcp::completion;
 use crate::mcp::server;
+use crate::mcp::server::content;
+use crate::mcp::server::resource;
 use crate::mcp::server::tool;
 
-use sipper::{Straw, sipper};
+use futures::future;
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sipper::{Sipper, Straw, sipper};
+use tokio::task;
 
+use std::collections::BTreeSet;
 use std::fmt;
 use std::io;
 use std::sync::Arc;
@@ -28,7 +46,7 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct Client {
     session: Session,
-    server: Server,
+    validate_output: bool,
 }
 
 impl Client {
@@ -37,119 +55,724 @@ impl Client {
         version: impl AsRef<str>,
         transport: impl Transport + Send + Sync + 'static,
     ) -> io::Result<Self> {
-        let mut session = Session {
-            transport: Arc::new(transport),
-            next_request: mcp::Id::default(),
-        };
+        Self::builder(name, version).connect(transport).await
+    }
 
-        let initialize = session
-            .request(request::Initialize {
+    pub fn builder(name: impl AsRef<str>, version: impl AsRef<str>) -> Builder {
+        Builder {
+            name: name.as_ref().to_owned(),
+            version: version.as_ref().to_owned(),
+            handlers: Handlers::default(),
+            validate_output: false,
+        }
+    }
+
+    async fn connect(
+        name: String,
+        version: String,
+        handlers: Handlers,
+        validate_output: bool,
+        transport: impl Transport + Send + Sync + 'static,
+    ) -> io::Result<Self> {
+        let transport: Arc<dyn Transport + Send + Sync> = Arc::new(transport);
+        let capabilities = handlers.capabilities();
+        let root_changes = handlers.roots.as_ref().map(Roots::changes);
+
+        let mut session = Session {
+            transport: transport.clone(),
+            initialize: request::Initialize {
                 protocol_version: mcp::VERSION.to_owned(),
-                capabilities: Capabilities {},
+                capabilities,
                 client_info: mcp::Client {
-                    name: name.as_ref().to_owned(),
+                    name,
                     title: None, // TODO
-                    version: version.as_ref().to_owned(),
+                    version,
                 },
-            })
-            .await?
-            .response::<server::response::Initialize>()
-            .await?;
+            },
+            version: mcp::Version::LATEST,
+            server: Server {
+                capabilities: server::Capabilities {
+                    tools: None,
+                    resources: None,
+                    prompts: None,
+                    logging: None,
+                    completions: None,
+                },
+                information: mcp::Server {
+                    name: String::new(),
+                    version: String::new(),
+                },
+            },
+            subscriptions: BTreeSet::new(),
+            log_level: None,
+            next_request: mcp::Id::default(),
+            next_progress: 0,
+            dispatcher: Dispatcher::new(transport, handlers),
+        };
 
-        if initialize.result.protocol_version != mcp::VERSION {
-            return Err(io::Error::new(
-                io::ErrorKind::Unsupported,
-                format!(
-                    "protocol mismatch (supported: {supported}, given: {given})",
-                    supported = mcp::VERSION,
-                    given = initialize.result.protocol_version,
-                ),
-            ));
-        }
+        session.initialize().await?;
 
-        let _ = session.notify(Notification::Initialized).await;
+        if let Some(changes) = root_changes {
+            drop(task::spawn(roots::notify_changes(
+                changes,
+                session.transport.clone(),
+            )));
+        }
 
         Ok(Self {
             session,
-            server: Server {
-                capabilities: initialize.result.capabilities,
-                information: initialize.result.server_info,
-            },
+            validate_output,
         })
     }
 
     pub fn server(&self) -> &Server {
-        &self.server
+        &self.session.server
+    }
+
+    /// Returns the protocol version negotiated with the server.
+    pub fn protocol_version(&self) -> mcp::Version {
+        self.session.version
     }
 
-    pub async fn list_tools(&mut self) -> io::Result<Vec<server::Tool>> {
-        let list = self.session.request(Request::ToolsList).await?;
+    /// Lists all the tools of the server.
+    ///
+    /// The list is cached until the server notifies that it has changed.
+    pub fn list_tools(&mut self) -> impl Straw<Vec<server::Tool>, Vec<server::Tool>, io::Error> {
+        sipper(async move |mut sender| {
+            let (cached, generation) = self.session.dispatcher.cached_tools();
+
+            if let Some(tools) = cached {
+                sender.send(tools.clone()).await;
+
+                return Ok(tools);
+            }
+
+            let tools = self.paginate(Self::list_tools_page).run(sender).await?;
+
+            self.session
+                .dispatcher
+                .cache_tools(tools.clone(), generation);
+
+            Ok(tools)
+        })
+    }
+
+    pub async fn list_tools_page(
+        &mut self,
+        cursor: Option<String>,
+    ) -> io::Result<Page<server::Tool>> {
+        let list = self
+            .session
+            .request(Request::ToolsList {
+                params: request::Paginated { cursor },
+            })
+            .await?;
 
         let mcp::Response {
-            result: server::response::ToolsList { tools },
+            result: server::response::ToolsList { tools, next_cursor },
             ..
         } = list.response().await?;
 
-        Ok(tools)
+        Ok(Page {
+            items: tools,
+            next_cursor,
+        })
     }
 
     pub fn call_tool(
         &mut self,
         name: impl AsRef<str>,
         arguments: mcp::Value,
-    ) -> impl Straw<tool::Response, Event, io::Error> {
+    ) -> impl Straw<tool::Response, Event, ToolError> {
         sipper(async move |mut sender| {
+            let progress_token = self.session.progress_token();
+
             let mut call = self
                 .session
                 .request(Request::ToolsCall {
                     params: request::ToolCall {
                         name: name.as_ref().to_owned(),
                         arguments,
+                        meta: Some(mcp::Meta {
+                            progress_token: Some(progress_token.clone()),
+                        }),
                     },
                 })
                 .await?;
 
             loop {
                 match call.next().await? {
-                    server::Message::Request(request) => {
-                        sender
-                            .send(Event::Request(request.id, request.payload))
-                            .await;
-                    }
-                    server::Message::Notification(notification) => {
-                        sender.send(Event::Notification(notification.payload)).await;
-                    }
+                    server::Message::Request(_) => {}
+                    server::Message::Notification(notification) => match notification.payload {
+                        server::Notification::Progress { params: progress }
+                            if progress.progress_token == progress_token =>
+                        {
+                            sender.send(Event::Progress(progress)).await;
+                        }
+                        notification => {
+                            sender.send(Event::Notification(notification)).await;
+                        }
+                    },
                     server::Message::Response(response) => {
+                        if self.validate_output {
+                            self.validate(name.as_ref(), &response.result)?;
+                        }
+
                         return Ok(response.result);
                     }
                     server::Message::Error(error) => {
-                        log::warn!("{error}");
+                        return Err(io::Error::from(error).into());
                     }
                 }
             }
         })
     }
+
+    /// Calls a tool with typed arguments and deserializes its structured
+    /// content into `T`.
+    ///
+    /// A tool result flagged as an error produces a [`ToolError::Failed`].
+    pub fn call_tool_as<T>(
+        &mut self,
+        name: impl AsRef<str>,
+        arguments: impl Serialize,
+    ) -> impl Straw<T, Event, ToolError>
+    where
+        T: DeserializeOwned,
+    {
+        sipper(async move |sender| {
+            if !self.session.version.supports_structured_output() {
+                return Err(ToolError::Io(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    format!(
+                        "structured tool output is not supported by protocol version {}",
+                        self.session.version
+                    ),
+                )));
+            }
+
+            let arguments = mcp::to_value(arguments)?;
+            let response = self.call_tool(name, arguments).run(sender).await?;
+
+            if response.is_error {
+                let text: Vec<_> = response
+                    .content
+                    .unstructured
+                    .into_iter()
+                    .filter_map(|content| match content {
+                        content::Unstructured::Text { text } => Some(text),
+                        _ => None,
+                    })
+                    .collect();
+
+                return Err(ToolError::Failed(text.join("\n")));
+            }
+
+            let Some(content) = response.content.structured else {
+                return Err(ToolError::Io(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "tool result has no structured content",
+                )));
+            };
+
+            Ok(mcp::from_value(content)?)
+        })
+    }
+
+    fn validate(&self, name: &str, response: &tool::Response) -> Result<(), ToolError> {
+        let Some(schema) = self.session.dispatcher.output_schema(name) else {
+            return Ok(());
+        };
+
+        response.validate(&schema).map_err(ToolError::InvalidOutput)
+    }
+
+    pub fn list_resources(
+        &mut self,
+    ) -> impl Straw<Vec<server::Resource>, Vec<server::Resource>, io::Error> {
+        self.paginate(Self::list_resources_page)
+    }
+
+    pub async fn list_resources_page(
+        &mut self,
+        cursor: Option<String>,
+    ) -> io::Result<Page<server::Resource>> {
+        let list = self
+            .session
+            .request(Request::ResourcesList {
+                params: request::Paginated { cursor },
+            })
+            .await?;
+
+        let mcp::Response {
+            result:
+                server::response::ResourcesList {
+                    resources,
+                    next_cursor,
+                },
+            ..
+        } = list.response().await?;
+
+        Ok(Page {
+            items: resources,
+            next_cursor,
+        })
+    }
+
+    pub fn list_resource_templates(
+        &mut self,
+    ) -> impl Straw<Vec<resource::Template>, Vec<resource::Template>, io::Error> {
+        self.paginate(Self::list_resource_templates_page)
+    }
+
+    pub async fn list_resource_templates_page(
+        &mut self,
+        cursor: Option<String>,
+    ) -> io::Result<Page<resource::Template>> {
+        let list = self
+            .session
+            .request(Request::ResourceTemplatesList {
+                params: request::Paginated { cursor },
+            })
+            .await?;
+
+        let mcp::Response {
+            result:
+                server::response::ResourceTemplatesList {
+                    resource_templates,
+                    next_cursor,
+                },
+            ..
+        } = list.response().await?;
+
+        Ok(Page {
+            items: resource_templates,
+            next_cursor,
+        })
+    }
+
+    pub async fn read_resource(
+        &mut self,
+        uri: impl AsRef<str>,
+    ) -> io::Result<Vec<resource::Contents>> {
+        let read = self
+            .session
+            .request(request::ResourceRead {
+                uri: uri.as_ref().to_owned(),
+            })
+            .await?;
+
+        let mcp::Response {
+            result: server::response::ResourcesRead { contents },
+            ..
+        } = read.response().await?;
+
+        Ok(contents)
+    }
+
+    pub fn list_prompts(
+        &mut self,
+    ) -> impl Straw<Vec<server::Prompt>, Vec<server::Prompt>, io::Error> {
+        self.paginate(Self::list_prompts_page)
+    }
+
+    pub async fn list_prompts_page(
+        &mut self,
+        cursor: Option<String>,
+    ) -> io::Result<Page<server::Prompt>> {
+        let list = self
+            .session
+            .request(Request::PromptsList {
+                params: request::Paginated { cursor },
+            })
+            .await?;
+
+        let mcp::Response {
+            result:
+                server::response::PromptsList {
+                    prompts,
+                    next_cursor,
+                },
+            ..
+        } = list.response().await?;
+
+        Ok(Page {
+            items: prompts,
+            next_cursor,
+        })
+    }
+
+    pub async fn get_prompt(
+        &mut self,
+        name: impl AsRef<str>,
+        arguments: impl IntoIterator<Item = (impl AsRef<str>, impl AsRef<str>)>,
+    ) -> io::Result<server::response::PromptsGet> {
+        let get = self
+            .session
+            .request(request::PromptGet {
+                name: name.as_ref().to_owned(),
+                arguments: arguments
+                    .into_iter()
+                    .map(|(name, value)| (name.as_ref().to_owned(), value.as_ref().to_owned()))
+                    .collect(),
+            })
+            .await?;
+
+        Ok(get.response().await?.result)
+    }
+
+    pub async fn subscribe(&mut self, uri: impl AsRef<str>) -> io::Result<Subscription> {
+        let uri = uri.as_ref().to_owned();
+        let notifications = self.session.dispatcher.subscribe();
+
+        let subscribe = self
+            .session
+            .request(request::Subscribe { uri: uri.clone() })
+            .await?;
+
+        let _ = subscribe.response::<mcp::Ignored>().await?;
+        let _ = self.session.subscriptions.insert(uri.clone());
+
+        Ok(Subscription::new(uri, notifications))
+    }
+
+    pub async fn unsubscribe(&mut self, uri: impl AsRef<str>) -> io::Result<()> {
+        let uri = uri.as_ref();
+
+        let unsubscribe = self
+            .session
+            .request(request::Unsubscribe {
+                uri: uri.to_owned(),
+            })
+            .await?;
+
+        let _ = unsubscribe.response::<mcp::Ignored>().await?;
+        let _ = self.session.subscriptions.remove(uri);
+
+        Ok(())
+    }
+
+    pub async fn complete(
+        &mut self,
+        reference: completion::Reference,
+        argument: impl AsRef<str>,
+        value: impl AsRef<str>,
+    ) -> io::Result<completion::Completion> {
+        let complete = self
+            .session
+            .request(request::Complete {
+                reference,
+                argument: completion::Argument {
+                    name: argument.as_ref().to_owned(),
+                    value: value.as_ref().to_owned(),
+                },
+                context: None,
+            })
+            .await?;
+
+        let mcp::Response {
+            result: server::response::Complete { completion },
+            ..
+        } = complete.response().await?;
+
+        Ok(completion)
+    }
+
+    pub async fn set_log_level(&mut self, level: mcp::logging::Level) -> io::Result<()> {
+        let set_level = self.session.request(request::SetLevel { level }).await?;
+
+        let _ = set_level.response::<mcp::Ignored>().await?;
+        self.session.log_level = Some(level);
+
+        Ok(())
+    }
+
+    /// Returns a stream of all the notifications sent by the server.
+    pub fn notifications(&self) -> impl Stream<Item = server::Notification> + use<> {
+        self.session.dispatcher.subscribe()
+    }
+
+    pub fn logs(&self) -> impl Stream<Item = mcp::logging::Message> + use<> {
+        self.session
+            .dispatcher
+            .subscribe()
+            .filter_map(|notification| {
+                future::ready(match notification {
+                    server::Notification::LoggingMessage { params: message } => Some(message),
+                    _ => None,
+                })
+            })
+    }
+
+    fn paginate<T>(
+        &mut self,
+        list: impl AsyncFn(&mut Self, Option<String>) -> io::Result<Page<T>>,
+    ) -> impl Straw<Vec<T>, Vec<T>, io::Error>
+    where
+        T: Clone,
+    {
+        sipper(async move |mut sender| {
+            let mut items = Vec::new();
+            let mut cursor = None;
+
+            loop {
+                let page = list(self, cursor).await?;
+
+                sender.send(page.items.clone()).await;
+                items.extend(page.items);
+
+                if page.next_cursor.is_none() {
+                    return Ok(items);
+                }
+
+                cursor = page.next_cursor;
+            }
+        })
+    }
+}
+
+pub struct Builder {
+    name: String,
+    version: String,
+    handlers: Handlers,
+    validate_output: bool,
+}
+
+impl Builder {
+    pub fn sampling(mut self, sampling: impl handler::Sampling + 'static) -> Self {
+        self.handlers.sampling = Some(Box::new(sampling));
+        self
+    }
+
+    pub fn elicitation(mut self, elicitation: impl handler::Elicitation + 'static) -> Self {
+        self.handlers.elicitation = Some(Box::new(elicitation));
+        self
+    }
+
+    pub fn roots(mut self, roots: Roots) -> Self {
+        self.handlers.roots = Some(roots);
+        self
+    }
+
+    /// Validates the structured content of tool results against the
+    /// output schema of their tool, as cached by [`Client::list_tools`].
+    ///
+    /// A mismatch produces a [`ToolError::InvalidOutput`].
+    pub fn validate_output(mut self, validate_output: bool) -> Self {
+        self.validate_output = validate_output;
+        self
+    }
+
+    pub async fn connect(
+        self,
+        transport: impl Transport + Send + Sync + 'static,
+    ) -> io::Result<Client> {
+        Client::connect(
+            self.name,
+            self.version,
+            self.handlers,
+            self.validate_output,
+            transport,
+        )
+        .await
+    }
+}
+
+impl fmt::Debug for Builder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("name", &self.name)
+            .field("version", &self.version)
+            .field("capabilities", &self.handlers.capabilities())
+            .field("validate_output", &self.validate_output)
+            .finish()
+    }
+}
+
+/// A single page of a list.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+/// The error of a typed tool call.
+#[derive(Debug)]
+pub enum ToolError {
+    /// The tool reported an error with the given text.
+    Failed(String),
+    /// The structured content of the result does not match the output
+    /// schema of the tool.
+    InvalidOutput(mcp::validation::Error),
+    /// The tool could not be called or its result could not be deserialized.
+    Io(io::Error),
+}
+
+impl fmt::Display for ToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Failed(text) => write!(f, "tool failed: {text}"),
+            Self::InvalidOutput(error) => write!(f, "invalid tool output: {error}"),
+            Self::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ToolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Failed(_) => None,
+            Self::InvalidOutput(error) => Some(error),
+            Self::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for ToolError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ToolError> for io::Error {
+    fn from(error: ToolError) -> Self {
+        match error {
+            ToolError::Failed(_) => io::Error::other(error),
+            ToolError::InvalidOutput(error) => error.into(),
+            ToolError::Io(error) => error,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum Event {
+    Progress(mcp::progress::Progress),
     Notification(server::Notification),
-    Request(mcp::Id, server::Request),
 }
 
 struct Session {
     transport: Arc<dyn Transport + Send + Sync>,
+    initialize: request::Initialize,
+    version: mcp::Version,
+    server: Server,
+    subscriptions: BTreeSet<String>,
+    log_level: Option<mcp::logging::Level>,
     next_request: mcp::Id,
+    next_progress: i64,
+    dispatcher: Dispatcher,
 }
 
 impl Session {
+    /// Initializes a new session with the server and starts listening
+    /// to its messages.
+    async fn initialize(&mut self) -> io::Result<()> {
+        let request = Request::from(self.initialize.clone());
+
+        let initialize = self
+            .send(&request)
+            .await?
+            .response::<server::response::Initialize>()
+            .await?
+            .result;
+
+        let version = initialize
+            .protocol_version
+            .parse()
+            .map_err(|error| io::Error::new(io::ErrorKind::Unsupported, error))?;
+
+        self.version = version;
+        self.server = Server {
+            capabilities: initialize.capabilities,
+            information: initialize.server_info,
+        };
+        self.transport.set_protocol_version(version);
+
+        let _ = self.notify(Notification::Initialized).await;
+
+        match self.transport.listen().await {
+            Ok(channel) => {
+                drop(task::spawn(self.dispatcher.clone().run(channel)));
+            }
+            Err(error) => {
+                log::debug!("Server messages are unavailable: {error}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Initializes a new session after the current one has expired,
+    /// restoring the state of the previous one.
+    async fn reinitialize(&mut self) -> io::Result<()> {
+        self.initialize().await?;
+        self.dispatcher.invalidate_tools();
+
+        if let Some(level) = self.log_level {
+            let _ = self
+                .send(&Request::from(request::SetLevel { level }))
+                .await?
+                .response::<mcp::Ignored>()
+                .await?;
+        }
+
+        for uri in self.subscriptions.clone() {
+            let _ = self
+                .send(&Request::from(request::Subscribe { uri }))
+                .await?
+                .response::<mcp::Ignored>()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends a request, initializing a new session first if the current
+    /// one has expired.
     async fn request(&mut self, request: impl Into<Request>) -> io::Result<Connection> {
         let request = request.into();
 
-        self.transport
-            .send(mcp::Request::new(self.next_request.increment(), request).serialize()?)
-            .await
-            .map(Connection::new)
+        match self.send(&request).await {
+            Err(error) if SessionExpired::is(&error) => {
+                log::debug!("Session expired; initializing a new one");
+
+                self.reinitialize().await?;
+
+                self.send(&request).await
+            }
+            result => result,
+        }
+    }
+
+    async fn send(&mut self, request: &Request) -> io::Result<Connection> {
+        let id = self.next_request.increment();
+        let bytes = mcp::Request::new(id, request).serialize()?;
+
+        let mut cancellation = Cancellation::new(id, self.dispatcher.clone());
+
+        // The initialize request must never be cancelled
+        if let Request::Initialize { .. } = request {
+            cancellation.disarm();
+        }
+
+        match self.transport.send(bytes).await {
+            Ok(channel) => Ok(Connection::new(
+                channel,
+                self.dispatcher.clone(),
+                cancellation,
+            )),
+            Err(error) => {
+                cancellation.disarm();
+
+                Err(error)
+            }
+        }
+    }
+
+    fn progress_token(&mut self) -> mcp::progress::Token {
+        let token = mcp::progress::Token::Integer(self.next_progress);
+        self.next_progress += 1;
+
+        token
     }
 
     async fn notify(&self, notification: impl Into<Notification>) -> io::Result<()> {
@@ -161,21 +784,13 @@ impl Session {
 
         Ok(())
     }
-
-    #[allow(unused)]
-    async fn response(&self, id: mcp::Id, response: Response) -> io::Result<()> {
-        self.transport
-            .send(mcp::Response::new(id, response).serialize()?)
-            .await;
-
-        Ok(())
-    }
 }
 
 impl fmt::Debug for Session {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Connection")
-            .field("next_request", &self.next_request) // TODO: Debug transport
+            .field("next_request", &self.next_request)
+            .field("next_progress", &self.next_progress) // TODO: Debug transport
             .finish()
     }
 }
@@ -195,3 +810,103 @@ impl Server {
         &self.information
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::{Bytes, json};
+    use crate::transport::Channel;
+
+    use futures::SinkExt;
+    use futures::channel::mpsc;
+    use futures::future::{BoxFuture, FutureExt};
+
+    /// A server with a single tool, whose output is given.
+    struct Mock {
+        output: mcp::Value,
+    }
+
+    impl Transport for Mock {
+        fn listen(&self) -> BoxFuture<'static, io::Result<Channel>> {
+            future::ready(Err(io::ErrorKind::Unsupported.into())).boxed()
+        }
+
+        fn send(&self, bytes: Bytes) -> BoxFuture<'static, io::Result<Channel>> {
+            let response = match mcp::client::Message::<mcp::Ignored>::deserialize(&bytes) {
+                Ok(mcp::client::Message::Request(request)) => {
+                    let result = match request.payload {
+                        Request::Initialize { .. } => json!({
+                            "protocolVersion": mcp::VERSION,
+                            "capabilities": { "tools": {} },
+                            "serverInfo": { "name": "mock", "version": "1" }
+                        }),
+                        Request::ToolsList { .. } => json!({
+                            "tools": [{
+                                "name": "weather",
+                                "description": "The weather",
+                                "inputSchema": { "type": "object", "properties": {} },
+                                "outputSchema": {
+                                    "type": "object",
+                                    "properties": { "temperature": { "type": "number" } },
+                                    "required": ["temperature"]
+                                }
+                            }]
+                        }),
+                        Request::ToolsCall { .. } => json!({
+                            "content": [],
+                            "structuredContent": self.output,
+                            "isError": false
+                        }),
+                        _ => json!({}),
+                    };
+
+                    Some(mcp::Response::new(request.id, result).serialize())
+                }
+                _ => None,
+            };
+
+            async move {
+                let (mut sender, receiver) = mpsc::channel(1);
+
+                if let Some(response) = response {
+                    let _ = sender.send(response?).await;
+                }
+
+                Ok(receiver)
+            }
+            .boxed()
+        }
+    }
+
+    async fn call(output: mcp::Value) -> Result<tool::Response, ToolError> {
+        let mut client = Client::builder("test", "1")
+            .validate_output(true)
+            .connect(Mock { output })
+            .await?;
+
+        let _ = client.list_tools().await?;
+
+        client.call_tool("weather", json!({})).await
+    }
+
+    #[tokio::test]
+    async fn valid_output() {
+        let response = call(json!({ "temperature": 21.5 })).await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn invalid_output() {
+        let error = call(json!({ "temperature": "hot" }))
+            .await
+            .expect_err("output must be invalid");
+
+        let ToolError::InvalidOutput(error) = error else {
+            panic!("expected invalid output, found: {error}");
+        };
+
+        assert_eq!(error.violations.len(), 1);
+        assert_eq!(error.violations[0].pointer, "/temperature");
+    }
+}
@@ -0,0 +1,86 @@
+use crate::mcp;
+use crate::mcp::client::{Notification, Root};
+use crate::transport::Transport;
+
+use futures::StreamExt;
+use futures::channel::mpsc;
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+#[derive(Debug, Clone, Default)]
+pub struct Roots {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    roots: Vec<Root>,
+    listeners: Vec<mpsc::UnboundedSender<()>>,
+}
+
+impl Roots {
+    pub fn new(roots: impl IntoIterator<Item = Root>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                roots: roots.into_iter().collect(),
+                listeners: Vec::new(),
+            })),
+        }
+    }
+
+    pub fn list(&self) -> Vec<Root> {
+        self.state().roots.clone()
+    }
+
+    pub fn set(&self, roots: impl IntoIterator<Item = Root>) {
+        self.update(|current| *current = roots.into_iter().collect());
+    }
+
+    pub fn add(&self, root: Root) {
+        self.update(|roots| {
+            roots.retain(|candidate| candidate.uri != root.uri);
+            roots.push(root);
+        });
+    }
+
+    pub fn remove(&self, uri: impl AsRef<str>) {
+        self.update(|roots| roots.retain(|root| root.uri != uri.as_ref()));
+    }
+
+    pub(crate) fn changes(&self) -> mpsc::UnboundedReceiver<()> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.state().listeners.push(sender);
+
+        receiver
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Vec<Root>)) {
+        let mut state = self.state();
+        f(&mut state.roots);
+
+        state
+            .listeners
+            .retain(|listener| listener.unbounded_send(()).is_ok());
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+pub(crate) async fn notify_changes(
+    mut changes: mpsc::UnboundedReceiver<()>,
+    transport: Arc<dyn Transport + Send + Sync>,
+) {
+    while changes.next().await.is_some() {
+        let result = match mcp::Notification::new(Notification::RootsListChanged).serialize() {
+            Ok(bytes) => transport.send(bytes).await.map(|_| ()),
+            Err(error) => Err(error.into()),
+        };
+
+        if let Err(error) = result {
+            log::error!("{error}");
+            return;
+        }
+    }
+}
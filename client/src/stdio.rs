@@ -1,6 +1,6 @@
-use crate::mcp::client::Message;
+use crate::mcp::client::{self, Message};
 use crate::mcp::server;
-use crate::mcp::{self, Bytes};
+use crate::mcp::{self, Bytes, progress};
 use crate::transport::{Channel, Transport};
 
 use futures::channel::mpsc;
@@ -10,6 +10,7 @@ use tokio::io::{self, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, Buf
 use tokio::process;
 use tokio::task;
 
+use std::collections::BTreeMap;
 use std::ffi::OsStr;
 
 pub struct Stdio {
@@ -76,6 +77,13 @@ enum Action {
     Send(Bytes, Sender),
 }
 
+/// A request awaiting its response.
+#[derive(Clone)]
+struct Pending {
+    sender: Sender,
+    progress: Option<progress::Token>,
+}
+
 async fn run(
     mut input: impl AsyncWrite + Unpin,
     output: impl AsyncRead + Unpin,
@@ -85,6 +93,7 @@ async fn run(
 
     let mut output = BufReader::new(output);
     let mut listeners = Vec::new();
+    let mut requests = BTreeMap::new();
     let mut buffer = Vec::new();
 
     loop {
@@ -94,9 +103,9 @@ async fn run(
             let next_event = future::select(next_line, next_action);
 
             match next_event.await {
-                Either::Left((line, _)) => Either::Left(line),
+                Either::Left((line, _)) => Either::Left(line?),
                 Either::Right((Some(action), _)) => Either::Right(action),
-                _ => return Ok(()),
+                Either::Right((None, _)) => return Ok(()),
             }
         };
 
@@ -104,46 +113,70 @@ async fn run(
             Either::Right(Action::Listen(sender)) => {
                 listeners.push(sender);
             }
-            Either::Left(Ok(n)) => {
-                if n == 0 {
-                    return Ok(());
-                }
+            Either::Right(Action::Send(bytes, sender)) => {
+                write(&mut input, &bytes).await?;
 
-                let bytes = Bytes::from_owner(std::mem::take(&mut buffer));
+                if let Ok(Message::Request(request)) = Message::<mcp::Ignored>::deserialize(&bytes)
+                {
+                    let progress = match request.payload {
+                        client::Request::ToolsCall { params } => {
+                            params.meta.and_then(|meta| meta.progress_token)
+                        }
+                        _ => None,
+                    };
 
-                for listener in &mut listeners {
-                    let _ = listener.send(bytes.clone()).await;
+                    let _ = requests.insert(request.id, Pending { sender, progress });
                 }
             }
-            Either::Right(Action::Send(bytes, mut sender)) => {
-                write(&mut input, &bytes).await?;
-
-                let Ok(Message::Request(_)) = Message::<mcp::Ignored>::deserialize(&bytes) else {
-                    continue;
+            Either::Left(0) => return Ok(()),
+            Either::Left(_) => {
+                let bytes = Bytes::from_owner(std::mem::take(&mut buffer));
+                requests.retain(|_, pending: &mut Pending| !pending.sender.is_closed());
+
+                let message = server::Message::<mcp::Ignored>::deserialize(&bytes);
+
+                // Responses and progress are routed to their request, while any
+                // other server message is sent to the listeners
+                let request = match &message {
+                    Ok(server::Message::Response(response)) => requests
+                        .remove(&response.id)
+                        .map(|pending| (pending, false)),
+                    Ok(server::Message::Error(error)) => error
+                        .id()
+                        .and_then(|id| requests.remove(&id))
+                        .map(|pending| (pending, false)),
+                    Ok(server::Message::Notification(mcp::Notification {
+                        payload: server::Notification::Progress { params: progress },
+                        ..
+                    })) => requests
+                        .values()
+                        .find(|pending| pending.progress.as_ref() == Some(&progress.progress_token))
+                        .map(|pending| (pending.clone(), true)),
+                    _ => None,
                 };
 
-                while let Ok(n) = output.read_until(0xA, &mut buffer).await {
-                    if n == 0 {
-                        return Ok(());
+                match request {
+                    // Progress is only informative; it is dropped if the
+                    // request is not keeping up
+                    Some((mut pending, true)) => {
+                        let _ = pending.sender.try_send(bytes);
                     }
+                    Some((mut pending, false)) => {
+                        drop(task::spawn(async move {
+                            let _ = pending.sender.send(bytes).await;
+                        }));
+                    }
+                    None => {
+                        listeners.retain(|listener: &Sender| !listener.is_closed());
 
-                    let bytes = Bytes::from_owner(std::mem::take(&mut buffer));
-                    let _ = sender.send(bytes.clone()).await;
-
-                    if let Ok(server::Message::Response(_)) =
-                        server::Message::<mcp::Ignored>::deserialize(&bytes)
-                    {
-                        break;
+                        for listener in &mut listeners {
+                            let _ = listener.send(bytes.clone()).await;
+                        }
                     }
                 }
             }
-            _ => {
-                break;
-            }
         }
     }
-
-    Ok(())
 }
 
 async fn write(input: &mut (impl AsyncWrite + Unpin), data: &[u8]) -> io::Result<()> {
@@ -0,0 +1,45 @@
+use crate::mcp::server::{self, notification};
+
+use futures::channel::mpsc;
+use futures::stream::{Stream, StreamExt};
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+#[derive(Debug)]
+pub struct Subscription {
+    uri: String,
+    notifications: mpsc::UnboundedReceiver<server::Notification>,
+}
+
+impl Subscription {
+    pub(crate) fn new(
+        uri: String,
+        notifications: mpsc::UnboundedReceiver<server::Notification>,
+    ) -> Self {
+        Self { uri, notifications }
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+}
+
+impl Stream for Subscription {
+    type Item = notification::ResourceUpdated;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.notifications.poll_next_unpin(cx) {
+                Poll::Ready(Some(server::Notification::ResourcesUpdated { params: update }))
+                    if update.uri == self.uri =>
+                {
+                    return Poll::Ready(Some(update));
+                }
+                Poll::Ready(Some(_)) => {}
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -1,14 +1,52 @@
-use crate::mcp::Bytes;
+use crate::mcp::{Bytes, Version};
 
 use futures::channel::mpsc;
 use futures::future::BoxFuture;
 
+use std::fmt;
 use std::io;
 
 pub trait Transport {
     fn listen(&self) -> BoxFuture<'static, io::Result<Channel>>;
 
     fn send(&self, bytes: Bytes) -> BoxFuture<'static, io::Result<Channel>>;
+
+    /// Sets the protocol version negotiated with the server.
+    ///
+    /// Transports that need to advertise the version in every message,
+    /// like HTTP, should override this.
+    fn set_protocol_version(&self, _version: Version) {}
 }
 
 pub type Channel = mpsc::Receiver<Bytes>;
+
+/// The error of a [`Transport`] when the server has ended the session
+/// of the client.
+///
+/// A [`Client`](crate::Client) initializes a new session when it
+/// encounters this error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionExpired;
+
+impl SessionExpired {
+    /// Returns `true` if the given error was caused by an expired session.
+    pub fn is(error: &io::Error) -> bool {
+        error
+            .get_ref()
+            .is_some_and(|error| error.is::<SessionExpired>())
+    }
+}
+
+impl fmt::Display for SessionExpired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the session has expired")
+    }
+}
+
+impl std::error::Error for SessionExpired {}
+
+impl From<SessionExpired> for io::Error {
+    fn from(error: SessionExpired) -> Self {
+        io::Error::new(io::ErrorKind::NotConnected, error)
+    }
+}
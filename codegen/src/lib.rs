@@ -0,0 +1,465 @@
+//! Generates typed bindings for the tools of an MCP server.
+//!
+//! The generated module contains a `Tools` struct with an async method per
+//! tool, together with the argument and output structs of every tool. It
+//! depends on `serde` with the `derive` feature.
+pub use techne_client as client;
+
+use crate::client::mcp::Schema;
+use crate::client::mcp::server::Tool;
+use crate::client::{Client, Transport};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::io;
+
+/// Connects to a server and generates bindings for all of its tools.
+pub async fn generate(transport: impl Transport + Send + Sync + 'static) -> io::Result<String> {
+    Generator::new().connect(transport).await
+}
+
+/// A generator of typed bindings.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    krate: String,
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Self {
+            krate: "::techne::client".to_owned(),
+        }
+    }
+
+    /// Sets the path of the client crate used by the generated code.
+    ///
+    /// By default, it is `::techne::client`.
+    pub fn krate(self, path: impl AsRef<str>) -> Self {
+        Self {
+            krate: path.as_ref().to_owned(),
+        }
+    }
+
+    /// Connects to a server and generates bindings for all of its tools.
+    pub async fn connect(
+        &self,
+        transport: impl Transport + Send + Sync + 'static,
+    ) -> io::Result<String> {
+        let mut client =
+            Client::new("techne-codegen", env!("CARGO_PKG_VERSION"), transport).await?;
+
+        let tools = client.list_tools().await?;
+
+        Ok(self.generate(&tools))
+    }
+
+    /// Generates bindings for the given tools.
+    pub fn generate(&self, tools: &[Tool]) -> String {
+        let krate = &self.krate;
+
+        let mut module = Module {
+            krate,
+            names: BTreeSet::new(),
+            definitions: Vec::new(),
+        };
+        let mut methods = String::new();
+        let mut method_names = BTreeSet::from_iter(INHERENT_METHODS.map(str::to_owned));
+
+        for tool in tools {
+            let method = unique(&mut method_names, snake_case(&tool.name));
+            let name = &tool.name;
+            let pascal = pascal_case(&tool.name);
+
+            let arguments = module.root(&tool.input_schema, &format!("{pascal}Arguments"));
+
+            let _ = writeln!(methods);
+            doc(&mut methods, "    ", &tool.description);
+
+            match &tool.output_schema {
+                Some(schema) => {
+                    let output = module.root(schema, &format!("{pascal}Output"));
+
+                    let _ = writeln!(
+                        methods,
+                        "    pub async fn {method}(&mut self, arguments: {arguments}) \
+                         -> Result<{output}, {krate}::ToolError> {{\n        \
+                         self.client.call_tool_as({name:?}, arguments).await\n    }}"
+                    );
+                }
+                None => {
+                    let _ = writeln!(
+                        methods,
+                        "    pub async fn {method}(&mut self, arguments: {arguments}) \
+                         -> Result<{krate}::mcp::server::tool::Response, {krate}::ToolError> {{\n        \
+                         let arguments = {krate}::mcp::to_value(arguments)?;\n\n        \
+                         self.client.call_tool({name:?}, arguments).await\n    }}"
+                    );
+                }
+            }
+        }
+
+        let mut output = String::new();
+
+        let _ = writeln!(
+            output,
+            "// This file was generated by techne-codegen. Do not edit it manually.\n\n\
+             /// The tools of the server.\n\
+             pub struct Tools<'a> {{\n    \
+             client: &'a mut {krate}::Client,\n\
+             }}\n\n\
+             impl<'a> Tools<'a> {{\n    \
+             pub fn new(client: &'a mut {krate}::Client) -> Self {{\n        \
+             Self {{ client }}\n    \
+             }}\n{methods}}}"
+        );
+
+        for definition in module.definitions {
+            let _ = writeln!(output, "\n{definition}");
+        }
+
+        output
+    }
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Module<'a> {
+    krate: &'a str,
+    names: BTreeSet<String>,
+    definitions: Vec<String>,
+}
+
+impl Module<'_> {
+    /// Returns the Rust type of the input or output of a tool.
+    ///
+    /// Objects always get their own struct, even if they have no properties.
+    fn root(&mut self, schema: &Schema, name: &str) -> String {
+        match schema {
+            Schema::Object {
+                description,
+                properties,
+                required,
+            } => self.define(name, description.as_deref(), properties, required),
+            _ => self.ty(schema, name),
+        }
+    }
+
+    /// Returns the Rust type of a schema, defining new structs as needed.
+    fn ty(&mut self, schema: &Schema, name: &str) -> String {
+        let krate = self.krate;
+
+        match schema {
+            Schema::Object { properties, .. } if properties.is_empty() => {
+                format!("{krate}::mcp::Map<String, {krate}::mcp::Value>")
+            }
+            Schema::Object {
+                description,
+                properties,
+                required,
+            } => self.define(name, description.as_deref(), properties, required),
+            Schema::String { .. } => "String".to_owned(),
+            Schema::Integer { .. } => "i64".to_owned(),
+            Schema::Number { .. } => "f64".to_owned(),
+            Schema::Boolean { .. } => "bool".to_owned(),
+            Schema::Array { items, .. } => {
+                let item = match items {
+                    Some(items) => self.ty(items, &format!("{name}Item")),
+                    None => format!("{krate}::mcp::Value"),
+                };
+
+                format!("Vec<{item}>")
+            }
+            Schema::Null => "()".to_owned(),
+        }
+    }
+
+    fn define(
+        &mut self,
+        name: &str,
+        description: Option<&str>,
+        properties: &BTreeMap<String, Schema>,
+        required: &[String],
+    ) -> String {
+        let name = unique(&mut self.names, name.to_owned());
+
+        let mut definition = String::new();
+        let mut fields = BTreeSet::new();
+
+        doc(&mut definition, "", description.unwrap_or_default());
+
+        let _ = write!(
+            definition,
+            "#[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]\n\
+             pub struct {name} {{"
+        );
+
+        if !properties.is_empty() {
+            definition.push('\n');
+        }
+
+        for (property, schema) in properties {
+            let field = unique(&mut fields, snake_case(property));
+            let ty = self.ty(schema, &format!("{name}{}", pascal_case(property)));
+            let is_required = required.contains(property);
+
+            doc(&mut definition, "    ", description_of(schema));
+
+            let mut attributes = Vec::new();
+
+            if field.trim_start_matches("r#") != property {
+                attributes.push(format!("rename = {property:?}"));
+            }
+
+            if !is_required {
+                attributes.push("default".to_owned());
+                attributes.push("skip_serializing_if = \"Option::is_none\"".to_owned());
+            }
+
+            if !attributes.is_empty() {
+                let _ = writeln!(definition, "    #[serde({})]", attributes.join(", "));
+            }
+
+            if is_required {
+                let _ = writeln!(definition, "    pub {field}: {ty},");
+            } else {
+                let _ = writeln!(definition, "    pub {field}: Option<{ty}>,");
+            }
+        }
+
+        definition.push('}');
+
+        self.definitions.push(definition);
+
+        name
+    }
+}
+
+fn description_of(schema: &Schema) -> &str {
+    match schema {
+        Schema::Object { description, .. }
+        | Schema::String { description }
+        | Schema::Integer { description }
+        | Schema::Number { description }
+        | Schema::Boolean { description }
+        | Schema::Array { description, .. } => description.as_deref().unwrap_or_default(),
+        Schema::Null => "",
+    }
+}
+
+fn doc(output: &mut String, indentation: &str, text: &str) {
+    for line in text.trim().lines() {
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            let _ = writeln!(output, "{indentation}///");
+        } else {
+            let _ = writeln!(output, "{indentation}/// {line}");
+        }
+    }
+}
+
+fn unique(names: &mut BTreeSet<String>, name: String) -> String {
+    let mut candidate = name.clone();
+    let mut i = 2;
+
+    while names.contains(&candidate) {
+        candidate = format!("{name}{i}");
+        i += 1;
+    }
+
+    let _ = names.insert(candidate.clone());
+
+    candidate
+}
+
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous: Option<char> = None;
+
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            words.extend((!word.is_empty()).then(|| std::mem::take(&mut word)));
+            previous = None;
+            continue;
+        }
+
+        let is_boundary = c.is_uppercase()
+            && previous.is_some_and(|previous| previous.is_lowercase() || previous.is_numeric());
+
+        if is_boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+
+        word.push(c);
+        previous = Some(c);
+    }
+
+    words.extend((!word.is_empty()).then_some(word));
+    words
+}
+
+fn snake_case(name: &str) -> String {
+    let snake = words(name)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    if snake.is_empty() || snake.starts_with(|c: char| c.is_numeric()) {
+        return format!("_{snake}");
+    }
+
+    match snake.as_str() {
+        "self" | "super" | "crate" => format!("{snake}_"),
+        keyword if KEYWORDS.contains(&keyword) => format!("r#{snake}"),
+        _ => snake,
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    let pascal: String = words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+
+            chars
+                .next()
+                .map(|first| {
+                    first
+                        .to_uppercase()
+                        .chain(chars.flat_map(char::to_lowercase))
+                })
+                .into_iter()
+                .flatten()
+                .collect::<String>()
+        })
+        .collect();
+
+    if pascal.is_empty() || pascal.starts_with(|c: char| c.is_numeric()) {
+        return format!("Tool{pascal}");
+    }
+
+    pascal
+}
+
+/// The methods of `Tools` that are not tools.
+const INHERENT_METHODS: [&str; 1] = ["new"];
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::mcp::{self, Value, json};
+
+    fn tool(name: &str, input_schema: Value, output_schema: Option<Value>) -> Tool {
+        Tool {
+            name: name.to_owned(),
+            title: None,
+            description: format!("Calls {name}"),
+            input_schema: schema(input_schema),
+            output_schema: output_schema.map(schema),
+        }
+    }
+
+    fn schema(schema: Value) -> Schema {
+        mcp::from_value(schema).expect("schema must be valid")
+    }
+
+    #[test]
+    fn snake_case_names() {
+        assert_eq!(snake_case("getWeather"), "get_weather");
+        assert_eq!(snake_case("get-weather"), "get_weather");
+        assert_eq!(snake_case("HTTPRequest"), "httprequest");
+        assert_eq!(snake_case("version2Info"), "version2_info");
+        assert_eq!(snake_case("2fa"), "_2fa");
+        assert_eq!(snake_case("!!"), "_");
+    }
+
+    #[test]
+    fn pascal_case_names() {
+        assert_eq!(pascal_case("get_weather"), "GetWeather");
+        assert_eq!(pascal_case("getWeather"), "GetWeather");
+        assert_eq!(pascal_case("list.files"), "ListFiles");
+        assert_eq!(pascal_case("2fa"), "Tool2fa");
+        assert_eq!(pascal_case(""), "Tool");
+    }
+
+    #[test]
+    fn keywords_are_escaped() {
+        assert_eq!(snake_case("type"), "r#type");
+        assert_eq!(snake_case("Match"), "r#match");
+        assert_eq!(snake_case("self"), "self_");
+        assert_eq!(snake_case("crate"), "crate_");
+    }
+
+    #[test]
+    fn unique_names() {
+        let mut names = BTreeSet::new();
+
+        assert_eq!(unique(&mut names, "search".to_owned()), "search");
+        assert_eq!(unique(&mut names, "search".to_owned()), "search2");
+        assert_eq!(unique(&mut names, "search".to_owned()), "search3");
+        assert_eq!(unique(&mut names, "search2".to_owned()), "search22");
+    }
+
+    #[test]
+    fn generated_module() {
+        let tools = [
+            tool(
+                "get_weather",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "city": { "type": "string", "description": "The city" },
+                        "days": { "type": "integer" }
+                    },
+                    "required": ["city"]
+                }),
+                Some(json!({
+                    "type": "object",
+                    "properties": { "temperature": { "type": "number" } },
+                    "required": ["temperature"]
+                })),
+            ),
+            tool("new", json!({ "type": "object", "properties": {} }), None),
+        ];
+
+        let output = Generator::new().krate("crate::client").generate(&tools);
+
+        assert!(output.contains(
+            "    pub async fn get_weather(&mut self, arguments: GetWeatherArguments) \
+             -> Result<GetWeatherOutput, crate::client::ToolError> {"
+        ));
+        assert!(output.contains("self.client.call_tool_as(\"get_weather\", arguments).await"));
+        assert!(output.contains(
+            "#[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]\n\
+             pub struct GetWeatherArguments {\n    \
+             /// The city\n    \
+             pub city: String,\n    \
+             #[serde(default, skip_serializing_if = \"Option::is_none\")]\n    \
+             pub days: Option<i64>,\n\
+             }"
+        ));
+        assert!(output.contains("pub struct GetWeatherOutput {\n    pub temperature: f64,\n}"));
+
+        // Tools cannot clash with the constructor of `Tools`
+        assert_eq!(output.matches("pub fn new(").count(), 1);
+        assert!(output.contains(
+            "    pub async fn new2(&mut self, arguments: NewArguments) \
+             -> Result<crate::client::mcp::server::tool::Response, crate::client::ToolError> {"
+        ));
+        assert!(output.contains("self.client.call_tool(\"new\", arguments).await"));
+    }
+}
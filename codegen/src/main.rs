@@ -0,0 +1,53 @@
+use techne_codegen::Generator;
+use techne_codegen::client::Stdio;
+
+use std::env;
+use std::io;
+use std::process;
+
+const USAGE: &str = "\
+Generates typed bindings for the tools of an MCP server.
+
+Usage:
+    techne-codegen [--crate <path>] --http <url>
+    techne-codegen [--crate <path>] <command> [<arguments>...]";
+
+#[tokio::main]
+pub async fn main() -> io::Result<()> {
+    let mut arguments = env::args().skip(1).peekable();
+    let mut generator = Generator::new();
+
+    if arguments.peek().map(String::as_str) == Some("--crate") {
+        let _ = arguments.next();
+
+        let Some(path) = arguments.next() else {
+            return usage();
+        };
+
+        generator = generator.krate(path);
+    }
+
+    let bindings = match arguments.next().as_deref() {
+        None | Some("-h" | "--help") => return usage(),
+        #[cfg(feature = "http")]
+        Some("--http") => {
+            let Some(url) = arguments.next() else {
+                return usage();
+            };
+
+            generator
+                .connect(techne_codegen::client::Http::new(url)?)
+                .await?
+        }
+        Some(command) => generator.connect(Stdio::run(command, arguments)?).await?,
+    };
+
+    print!("{bindings}");
+
+    Ok(())
+}
+
+fn usage() -> io::Result<()> {
+    eprintln!("{USAGE}");
+    process::exit(2)
+}
@@ -1,5 +1,6 @@
 use techne::Client;
 use techne::client::{Http, Stdio};
+use techne::mcp::completion::Reference;
 use techne::mcp::json;
 
 use std::env;
@@ -29,8 +30,24 @@ pub async fn main() -> io::Result<()> {
         .call_tool("say_hello", json!({ "name": "World" }))
         .await?;
 
+    let resources = client.list_resources().await?;
+    let greeting = client.read_resource("techne://greetings/World").await?;
+
+    let prompts = client.list_prompts().await?;
+    let review = client
+        .get_prompt("review", [("code", "fn main() {}"), ("focus", "style")])
+        .await?;
+    let focus = client
+        .complete(Reference::prompt("review"), "focus", "p")
+        .await?;
+
     dbg!(tools);
     dbg!(hello);
+    dbg!(resources);
+    dbg!(greeting);
+    dbg!(prompts);
+    dbg!(review);
+    dbg!(focus);
 
     Ok(())
 }
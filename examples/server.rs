@@ -1,3 +1,5 @@
+use techne::server::prompt::{self, prompt_2};
+use techne::server::resource::{self, resource, template};
 use techne::server::tool::{string, tool, tool_2, u32};
 use techne::server::{self, Server};
 
@@ -24,7 +26,32 @@ pub async fn main() -> io::Result<()> {
         .description("Adds two integers"),
     ];
 
-    server.tools(tools).run(transport).await
+    let resources = [resource("techne://readme", readme)
+        .name("readme")
+        .description("The README of the project")
+        .mime_type("text/markdown")];
+
+    let templates = [template("techne://greetings/{name}", greeting)
+        .name("greeting")
+        .description("A greeting for someone")
+        .mime_type("text/plain")];
+
+    let prompts = [prompt_2(
+        review,
+        prompt::string("code", "The code to review"),
+        prompt::optional(prompt::string("focus", "The aspect to focus on")),
+    )
+    .name("review")
+    .description("Asks for a code review")
+    .completion("focus", complete_focus)];
+
+    server
+        .tools(tools)
+        .resources(resources)
+        .resource_templates(templates)
+        .prompts(prompts)
+        .run(transport)
+        .await
 }
 
 async fn say_hello(name: String) -> String {
@@ -34,3 +61,24 @@ async fn say_hello(name: String) -> String {
 async fn add(a: u32, b: u32) -> u32 {
     a + b
 }
+
+async fn readme() -> &'static str {
+    include_str!("../README.md")
+}
+
+async fn greeting(variables: resource::Variables) -> String {
+    format!("Hello, {}!", variables.get("name").unwrap_or_default())
+}
+
+async fn review(code: String, focus: Option<String>) -> String {
+    let focus = focus.unwrap_or_else(|| "anything".to_owned());
+
+    format!("Please review this code focusing on {focus}:\n\n{code}")
+}
+
+async fn complete_focus(value: String) -> Vec<&'static str> {
+    ["correctness", "performance", "readability", "style"]
+        .into_iter()
+        .filter(|focus| focus.starts_with(&value))
+        .collect()
+}
@@ -0,0 +1,229 @@
+mod schematic;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, DeriveInput, Error, Expr, FnArg, ItemFn, Lit, Meta, Pat, Type, parse_macro_input,
+};
+
+/// Turns an `async fn` into a function that returns a `Tool`.
+///
+/// The name of the tool is the name of the function, its description is
+/// taken from the doc comments of the function, and its input schema is
+/// derived from the parameters and their doc comments.
+///
+/// The first parameter may be a `Context`, written as `tool::Context` or
+/// with its full path. A `Context` imported under any other name must be
+/// marked with `#[context]`.
+#[proc_macro_attribute]
+pub fn tool(attribute: TokenStream, item: TokenStream) -> TokenStream {
+    if !attribute.is_empty() {
+        return Error::new(
+            Span::call_site(),
+            "the tool attribute does not take any arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let function = parse_macro_input!(item as ItemFn);
+
+    expand(function)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives the `Schematic` trait of `techne::mcp` for a type.
+///
+/// Structs with named fields are described as objects, and enums with
+/// unit variants as strings. Doc comments become descriptions, and the
+/// `serde` attributes that rename or skip fields are taken into account.
+///
+/// A `Schema` cannot restrict the values of a string, so the variants of
+/// an enum are only listed in its description. Unknown values pass
+/// schema validation, but fail to deserialize with an error that names
+/// the argument and the expected variants.
+///
+/// Structs with named fields also implement `Object`, so they can be
+/// wrapped in `Structured` and returned by tools. Marking them with
+/// `#[schematic(response)]` lets tools return them directly.
+#[proc_macro_derive(Schematic, attributes(serde, schematic))]
+pub fn derive_schematic(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    schematic::expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(mut function: ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let signature = &function.sig;
+
+    if signature.asyncness.is_none() {
+        return Err(Error::new(
+            signature.fn_token.span(),
+            "tools must be async functions",
+        ));
+    }
+
+    if !signature.generics.params.is_empty() {
+        return Err(Error::new(
+            signature.generics.span(),
+            "tools cannot be generic",
+        ));
+    }
+
+    let name = signature.ident.to_string();
+    let description = documentation(&function.attrs);
+
+    let mut has_context = false;
+    let mut arguments = Vec::new();
+
+    for (i, input) in function.sig.inputs.iter_mut().enumerate() {
+        let FnArg::Typed(parameter) = input else {
+            return Err(Error::new(input.span(), "tools cannot take `self`"));
+        };
+
+        let is_marked = parameter
+            .attrs
+            .iter()
+            .any(|attribute| attribute.path().is_ident("context"));
+
+        if is_marked {
+            if i != 0 {
+                return Err(Error::new(
+                    parameter.span(),
+                    "only the first parameter can be a context",
+                ));
+            }
+
+            parameter
+                .attrs
+                .retain(|attribute| !attribute.path().is_ident("context"));
+        }
+
+        if i == 0 && (is_marked || is_context(&parameter.ty)) {
+            has_context = true;
+            continue;
+        }
+
+        let Pat::Ident(pattern) = parameter.pat.as_ref() else {
+            return Err(Error::new(
+                parameter.pat.span(),
+                "tool parameters must be plain identifiers",
+            ));
+        };
+
+        let description = documentation(&parameter.attrs);
+        parameter
+            .attrs
+            .retain(|attribute| !attribute.path().is_ident("doc"));
+
+        arguments.push((pattern.ident.clone(), (*parameter.ty).clone(), description));
+    }
+
+    let visibility = std::mem::replace(&mut function.vis, syn::Visibility::Inherited);
+    let attributes = std::mem::take(&mut function.attrs);
+    let ident = function.sig.ident.clone();
+
+    let declarations = arguments.iter().map(|(ident, ty, description)| {
+        let name = ident.to_string();
+
+        quote! {
+            let #ident = signature.argument(
+                <#ty as ::techne::server::tool::Parameter>::argument(#name, #description)
+            );
+        }
+    });
+
+    let takes = arguments.iter().map(|(ident, _, _)| {
+        quote! {
+            let #ident = arguments.take(&#ident)?;
+        }
+    });
+
+    let idents: Vec<_> = arguments.iter().map(|(ident, _, _)| ident).collect();
+
+    let call = if has_context {
+        quote! { #ident(context, #(#idents),*) }
+    } else {
+        quote! { #ident(#(#idents),*) }
+    };
+
+    let context = if has_context {
+        quote! { context }
+    } else {
+        quote! { _context }
+    };
+
+    Ok(quote! {
+        #(#attributes)*
+        #visibility fn #ident() -> ::techne::server::Tool {
+            #function
+
+            let mut signature = ::techne::server::tool::Signature::new();
+
+            #(#declarations)*
+
+            ::techne::server::tool::from_signature(signature, move |#context, mut arguments| {
+                #(#takes)*
+
+                Ok(#call)
+            })
+            .name(#name)
+            .description(#description)
+        }
+    })
+}
+
+fn documentation(attributes: &[Attribute]) -> String {
+    let lines: Vec<_> = attributes
+        .iter()
+        .filter_map(|attribute| {
+            let Meta::NameValue(meta) = &attribute.meta else {
+                return None;
+            };
+
+            if !meta.path.is_ident("doc") {
+                return None;
+            }
+
+            let Expr::Lit(expression) = &meta.value else {
+                return None;
+            };
+
+            let Lit::Str(line) = &expression.lit else {
+                return None;
+            };
+
+            Some(line.value().trim().to_owned())
+        })
+        .collect();
+
+    lines.join("\n").trim().to_owned()
+}
+
+fn is_context(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+
+    if path.qself.is_some() {
+        return false;
+    }
+
+    let segments = &path.path.segments;
+
+    // `tool::Context`, `server::tool::Context` or `techne::server::tool::Context`
+    (2..=CONTEXT.len()).contains(&segments.len())
+        && (path.path.leading_colon.is_none() || segments.len() == CONTEXT.len())
+        && segments
+            .iter()
+            .rev()
+            .zip(CONTEXT.iter().rev())
+            .all(|(segment, name)| segment.ident == name && segment.arguments.is_none())
+}
+
+const CONTEXT: &[&str] = &["techne", "server", "tool", "Context"];
@@ -0,0 +1,563 @@
+use crate::documentation;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Expr, Fields, GenericParam, Generics, LitStr, Token,
+    parenthesized, parse_quote,
+};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let container = Serde::parse(&input.attrs)?;
+
+    let is_object =
+        matches!(&input.data, Data::Struct(data) if matches!(data.fields, Fields::Named(_)));
+
+    let is_response = is_response(&input.attrs)?;
+
+    if is_response && !is_object {
+        return Err(Error::new(
+            input.ident.span(),
+            "only structs with named fields can be responses",
+        ));
+    }
+    let description = documentation(&input.attrs);
+
+    if container.tag {
+        return Err(Error::new(
+            input.ident.span(),
+            "only externally tagged types are supported",
+        ));
+    }
+
+    let schema = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let mut properties = Vec::new();
+
+                for field in &fields.named {
+                    let attributes = Serde::parse(&field.attrs)?;
+
+                    if attributes.skip {
+                        continue;
+                    }
+
+                    if attributes.flatten {
+                        return Err(Error::new(
+                            field.span(),
+                            "flattened fields are not supported",
+                        ));
+                    }
+
+                    let ident = field.ident.as_ref().expect("named field");
+
+                    let name = attributes.rename.unwrap_or_else(|| {
+                        let name = ident.to_string();
+                        let name = name.trim_start_matches("r#");
+
+                        match &container.rename_all {
+                            Some(case) => case.field(name),
+                            None => name.to_owned(),
+                        }
+                    });
+
+                    let ty = &field.ty;
+                    let schema = describe(
+                        quote! { <#ty as ::techne::mcp::Schematic>::schema() },
+                        &documentation(&field.attrs),
+                    );
+
+                    let is_optional = if container.default || attributes.default {
+                        quote! { true }
+                    } else {
+                        quote! { <#ty as ::techne::mcp::Schematic>::is_optional() }
+                    };
+
+                    properties.push(quote! {
+                        let _ = properties.insert(#name.to_owned(), #schema);
+
+                        if !#is_optional {
+                            required.push(#name.to_owned());
+                        }
+                    });
+                }
+
+                quote! {
+                    let mut properties = ::std::collections::BTreeMap::new();
+                    let mut required = ::std::vec::Vec::new();
+
+                    #(#properties)*
+
+                    ::techne::mcp::Schema::Object {
+                        description: None,
+                        properties,
+                        required,
+                    }
+                }
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+
+                quote! { <#ty as ::techne::mcp::Schematic>::schema() }
+            }
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "only structs with named fields or a single unnamed field are supported",
+                ));
+            }
+        },
+        Data::Enum(data) => {
+            let mut variants = Vec::new();
+
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(Error::new(
+                        variant.span(),
+                        "only enums with unit variants are supported",
+                    ));
+                }
+
+                let attributes = Serde::parse(&variant.attrs)?;
+
+                if attributes.skip {
+                    continue;
+                }
+
+                variants.push(attributes.rename.unwrap_or_else(|| {
+                    let name = variant.ident.to_string();
+
+                    match &container.rename_all {
+                        Some(case) => case.variant(&name),
+                        None => name,
+                    }
+                }));
+            }
+
+            let values = variants
+                .iter()
+                .map(|variant| format!("`{variant}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let values = format!("One of: {values}.");
+
+            let description = if description.is_empty() {
+                values
+            } else {
+                format!("{description}\n\n{values}")
+            };
+
+            return Ok(implementation(
+                input,
+                quote! {
+                    ::techne::mcp::Schema::String {
+                        description: Some(#description.to_owned()),
+                    }
+                },
+            ));
+        }
+        Data::Union(_) => {
+            return Err(Error::new(input.ident.span(), "unions are not supported"));
+        }
+    };
+
+    let schema = describe(quote! { { #schema } }, &description);
+
+    let object = if is_object {
+        object(&input)
+    } else {
+        TokenStream::new()
+    };
+
+    let response = if is_response {
+        response(&input)
+    } else {
+        TokenStream::new()
+    };
+
+    let implementation = implementation(input, schema);
+
+    Ok(quote! {
+        #implementation
+        #object
+        #response
+    })
+}
+
+/// Returns `true` if the type is marked with `#[schematic(response)]`.
+fn is_response(attributes: &[Attribute]) -> syn::Result<bool> {
+    let mut is_response = false;
+
+    for attribute in attributes {
+        if !attribute.path().is_ident("schematic") {
+            continue;
+        }
+
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("response") {
+                is_response = true;
+
+                Ok(())
+            } else {
+                Err(meta.error("unknown schematic attribute"))
+            }
+        })?;
+    }
+
+    Ok(is_response)
+}
+
+fn implementation(input: DeriveInput, schema: TokenStream) -> TokenStream {
+    let generics = generics(&input);
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::techne::mcp::Schematic for #ident #ty_generics #where_clause {
+            fn schema() -> ::techne::mcp::Schema {
+                #schema
+            }
+        }
+    }
+}
+
+fn object(input: &DeriveInput) -> TokenStream {
+    let generics = generics(input);
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::techne::mcp::schematic::Object for #ident #ty_generics #where_clause {}
+    }
+}
+
+fn response(input: &DeriveInput) -> TokenStream {
+    let generics = generics(input);
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::techne::mcp::server::tool::IntoResponse for #ident #ty_generics #where_clause {
+            type Content = Self;
+
+            fn into_outcome(self) -> ::techne::mcp::server::tool::Response<Self> {
+                <::techne::mcp::server::tool::Structured<Self> as ::techne::mcp::server::tool::IntoResponse>::into_outcome(
+                    ::techne::mcp::server::tool::Structured(self),
+                )
+            }
+
+            fn output_schema() -> ::std::option::Option<::techne::mcp::Schema> {
+                <::techne::mcp::server::tool::Structured<Self> as ::techne::mcp::server::tool::IntoResponse>::output_schema()
+            }
+        }
+    }
+}
+
+/// Returns the generics of the type, with every type parameter bound
+/// to `Schematic`.
+fn generics(input: &DeriveInput) -> Generics {
+    let mut generics = input.generics.clone();
+
+    for parameter in &mut generics.params {
+        if let GenericParam::Type(parameter) = parameter {
+            parameter
+                .bounds
+                .push(parse_quote!(::techne::mcp::Schematic));
+        }
+    }
+
+    generics
+}
+
+fn describe(schema: TokenStream, description: &str) -> TokenStream {
+    if description.is_empty() {
+        return schema;
+    }
+
+    quote! {
+        ::techne::mcp::schematic::describe(#schema, #description)
+    }
+}
+
+/// The `serde` attributes that change the shape of a type.
+#[derive(Default)]
+struct Serde {
+    rename: Option<String>,
+    rename_all: Option<Case>,
+    default: bool,
+    skip: bool,
+    flatten: bool,
+    tag: bool,
+}
+
+impl Serde {
+    fn parse(attributes: &[Attribute]) -> syn::Result<Self> {
+        let mut serde = Self::default();
+
+        for attribute in attributes {
+            if !attribute.path().is_ident("serde") {
+                continue;
+            }
+
+            attribute.parse_nested_meta(|meta| {
+                let path = &meta.path;
+
+                if path.is_ident("rename") {
+                    serde.rename = Some(name(&meta)?);
+                } else if path.is_ident("rename_all") {
+                    let case = name(&meta)?;
+
+                    serde.rename_all = Some(Case::parse(&case).ok_or_else(|| {
+                        Error::new(meta.path.span(), format!("unknown case: {case}"))
+                    })?);
+                } else {
+                    if path.is_ident("default") {
+                        serde.default = true;
+                    } else if path.is_ident("skip") || path.is_ident("skip_deserializing") {
+                        serde.skip = true;
+                    } else if path.is_ident("flatten") {
+                        serde.flatten = true;
+                    } else if path.is_ident("tag") || path.is_ident("untagged") {
+                        serde.tag = true;
+                    }
+
+                    skip(&meta)?;
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(serde)
+    }
+}
+
+/// Parses either `name = "..."` or `name(deserialize = "...")`.
+fn name(meta: &syn::meta::ParseNestedMeta<'_>) -> syn::Result<String> {
+    if meta.input.peek(Token![=]) {
+        return Ok(meta.value()?.parse::<LitStr>()?.value());
+    }
+
+    let mut name = None;
+
+    meta.parse_nested_meta(|meta| {
+        let value = meta.value()?.parse::<LitStr>()?.value();
+
+        if meta.path.is_ident("deserialize") {
+            name = Some(value);
+        }
+
+        Ok(())
+    })?;
+
+    name.ok_or_else(|| meta.error("expected a `deserialize` name"))
+}
+
+fn skip(meta: &syn::meta::ParseNestedMeta<'_>) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        let _ = meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        let content;
+        parenthesized!(content in meta.input);
+        let _ = content.parse::<TokenStream>()?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl Case {
+    fn parse(case: &str) -> Option<Self> {
+        Some(match case {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    /// Renames a `snake_case` field.
+    fn field(self, name: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => name.to_owned(),
+            Self::Upper | Self::ScreamingSnake => name.to_ascii_uppercase(),
+            Self::Pascal | Self::Camel => {
+                let mut pascal = String::new();
+                let mut capitalize = self == Self::Pascal;
+
+                for c in name.chars() {
+                    if c == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(c.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(c);
+                    }
+                }
+
+                pascal
+            }
+            Self::Kebab => name.replace('_', "-"),
+            Self::ScreamingKebab => name.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+
+    /// Renames a `PascalCase` variant.
+    fn variant(self, name: &str) -> String {
+        match self {
+            Self::Lower => name.to_ascii_lowercase(),
+            Self::Upper => name.to_ascii_uppercase(),
+            Self::Pascal => name.to_owned(),
+            Self::Camel => {
+                let mut chars = name.chars();
+
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            }
+            Self::Snake | Self::ScreamingSnake | Self::Kebab | Self::ScreamingKebab => {
+                let mut snake = String::new();
+
+                for (i, c) in name.char_indices() {
+                    if i > 0 && c.is_uppercase() {
+                        snake.push('_');
+                    }
+
+                    snake.push(c.to_ascii_lowercase());
+                }
+
+                match self {
+                    Self::ScreamingSnake => snake.to_ascii_uppercase(),
+                    Self::Kebab => snake.replace('_', "-"),
+                    Self::ScreamingKebab => snake.replace('_', "-").to_ascii_uppercase(),
+                    _ => snake,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expansion(input: DeriveInput) -> String {
+        expand(input).expect("input must be valid").to_string()
+    }
+
+    #[test]
+    fn renamed_fields() {
+        let cases = [
+            (Case::Lower, "user_id"),
+            (Case::Upper, "USER_ID"),
+            (Case::Pascal, "UserId"),
+            (Case::Camel, "userId"),
+            (Case::Snake, "user_id"),
+            (Case::ScreamingSnake, "USER_ID"),
+            (Case::Kebab, "user-id"),
+            (Case::ScreamingKebab, "USER-ID"),
+        ];
+
+        for (case, expected) in cases {
+            assert_eq!(case.field("user_id"), expected);
+        }
+    }
+
+    #[test]
+    fn renamed_variants() {
+        let cases = [
+            (Case::Lower, "userid"),
+            (Case::Upper, "USERID"),
+            (Case::Pascal, "UserId"),
+            (Case::Camel, "userId"),
+            (Case::Snake, "user_id"),
+            (Case::ScreamingSnake, "USER_ID"),
+            (Case::Kebab, "user-id"),
+            (Case::ScreamingKebab, "USER-ID"),
+        ];
+
+        for (case, expected) in cases {
+            assert_eq!(case.variant("UserId"), expected);
+        }
+
+        assert_eq!(Case::Camel.variant("Écran"), "Écran");
+        assert_eq!(Case::Camel.variant(""), "");
+    }
+
+    #[test]
+    fn enum_values_are_described() {
+        let expansion = expansion(parse_quote! {
+            #[serde(rename_all = "camelCase")]
+            enum Unit {
+                Celsius,
+                DegreesFahrenheit,
+            }
+        });
+
+        assert!(expansion.contains("One of: `celsius`, `degreesFahrenheit`."));
+    }
+
+    #[test]
+    fn objects() {
+        let expansion = expansion(parse_quote! {
+            struct Weather {
+                temperature: f64,
+            }
+        });
+
+        assert!(expansion.contains(":: techne :: mcp :: schematic :: Object for Weather"));
+        assert!(!expansion.contains("IntoResponse"));
+    }
+
+    #[test]
+    fn responses() {
+        let expansion = expansion(parse_quote! {
+            #[schematic(response)]
+            struct Weather {
+                temperature: f64,
+            }
+        });
+
+        assert!(expansion.contains(":: techne :: mcp :: schematic :: Object for Weather"));
+        assert!(expansion.contains("IntoResponse for Weather"));
+    }
+
+    #[test]
+    fn non_objects() {
+        let expansion = expansion(parse_quote! {
+            struct Celsius(f64);
+        });
+
+        assert!(!expansion.contains("Object for"));
+
+        let error = expand(parse_quote! {
+            #[schematic(response)]
+            enum Unit {
+                Celsius,
+                Fahrenheit,
+            }
+        });
+
+        assert!(error.is_err());
+    }
+}
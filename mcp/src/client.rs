@@ -1,12 +1,16 @@
 pub mod capabilities;
+pub mod elicitation;
 pub mod notification;
 pub mod request;
 pub mod response;
+pub mod root;
+pub mod sampling;
 
 pub use capabilities::Capabilities;
 pub use notification::Notification;
 pub use request::Request;
 pub use response::Response;
+pub use root::Root;
 
 pub type Message<T = Response> = crate::Message<Request, Notification, T>;
 
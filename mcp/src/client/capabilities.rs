@@ -1,8 +1,28 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Capabilities {
-    // TODO: Roots
-    // TODO: Sampling
-    // TODO: Elicitation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roots: Option<Roots>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<Sampling>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elicitation: Option<Elicitation>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Roots {
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub list_changed: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Sampling {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Elicitation {}
+
+fn is_false(b: &bool) -> bool {
+    !b
 }
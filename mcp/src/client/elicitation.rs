@@ -0,0 +1,63 @@
+use crate::{Map, Value};
+
+use serde::{Deserialize, Serialize};
+
+use std::io;
+
+/// A restricted [`Schema`](crate::Schema) that can be used to request
+/// user input.
+///
+/// It must be an object and its properties must be primitive types.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "crate::Schema", into = "crate::Schema")]
+pub struct Schema(crate::Schema);
+
+impl Schema {
+    pub fn as_schema(&self) -> &crate::Schema {
+        &self.0
+    }
+}
+
+impl TryFrom<crate::Schema> for Schema {
+    type Error = io::Error;
+
+    fn try_from(schema: crate::Schema) -> Result<Self, Self::Error> {
+        let crate::Schema::Object { properties, .. } = &schema else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "elicitation schema must be an object",
+            ));
+        };
+
+        for (name, property) in properties {
+            match property {
+                crate::Schema::String { .. }
+                | crate::Schema::Integer { .. }
+                | crate::Schema::Number { .. }
+                | crate::Schema::Boolean { .. } => {}
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("elicitation property must be a primitive type: {name}"),
+                    ));
+                }
+            }
+        }
+
+        Ok(Self(schema))
+    }
+}
+
+impl From<Schema> for crate::Schema {
+    fn from(schema: Schema) -> Self {
+        schema.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum Response<T = Map<String, Value>> {
+    Accept { content: T },
+    Decline,
+    Cancel,
+}
@@ -1,8 +1,20 @@
+use crate::Cancelled;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "method")]
 pub enum Notification {
-    #[serde(rename = "initialized")]
+    #[serde(rename = "notifications/cancelled")]
+    Cancelled { params: Cancelled },
+    #[serde(rename = "notifications/initialized")]
     Initialized,
+    #[serde(rename = "notifications/roots/list_changed")]
+    RootsListChanged,
+}
+
+impl From<Cancelled> for Notification {
+    fn from(cancelled: Cancelled) -> Self {
+        Self::Cancelled { params: cancelled }
+    }
 }
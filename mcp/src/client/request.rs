@@ -1,7 +1,10 @@
 use crate::client::{self, Client};
+use crate::{Meta, completion, logging};
 
 use serde::{Deserialize, Serialize};
 
+use std::collections::BTreeMap;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "method")]
 pub enum Request {
@@ -10,9 +13,45 @@ pub enum Request {
     #[serde(rename = "ping")]
     Ping,
     #[serde(rename = "tools/list")]
-    ToolsList,
+    ToolsList {
+        #[serde(default)]
+        params: Paginated,
+    },
     #[serde(rename = "tools/call")]
     ToolsCall { params: ToolCall },
+    #[serde(rename = "resources/list")]
+    ResourcesList {
+        #[serde(default)]
+        params: Paginated,
+    },
+    #[serde(rename = "resources/templates/list")]
+    ResourceTemplatesList {
+        #[serde(default)]
+        params: Paginated,
+    },
+    #[serde(rename = "resources/read")]
+    ResourcesRead { params: ResourceRead },
+    #[serde(rename = "resources/subscribe")]
+    ResourcesSubscribe { params: Subscribe },
+    #[serde(rename = "resources/unsubscribe")]
+    ResourcesUnsubscribe { params: Unsubscribe },
+    #[serde(rename = "prompts/list")]
+    PromptsList {
+        #[serde(default)]
+        params: Paginated,
+    },
+    #[serde(rename = "prompts/get")]
+    PromptsGet { params: PromptGet },
+    #[serde(rename = "logging/setLevel")]
+    LoggingSetLevel { params: SetLevel },
+    #[serde(rename = "completion/complete")]
+    CompletionComplete { params: Complete },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Paginated {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +72,8 @@ impl From<Initialize> for Request {
 pub struct ToolCall {
     pub name: String,
     pub arguments: serde_json::Value,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
 }
 
 impl From<ToolCall> for Request {
@@ -40,3 +81,77 @@ impl From<ToolCall> for Request {
         Self::ToolsCall { params: call }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceRead {
+    pub uri: String,
+}
+
+impl From<ResourceRead> for Request {
+    fn from(read: ResourceRead) -> Self {
+        Self::ResourcesRead { params: read }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subscribe {
+    pub uri: String,
+}
+
+impl From<Subscribe> for Request {
+    fn from(subscribe: Subscribe) -> Self {
+        Self::ResourcesSubscribe { params: subscribe }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Unsubscribe {
+    pub uri: String,
+}
+
+impl From<Unsubscribe> for Request {
+    fn from(unsubscribe: Unsubscribe) -> Self {
+        Self::ResourcesUnsubscribe {
+            params: unsubscribe,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptGet {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub arguments: BTreeMap<String, String>,
+}
+
+impl From<PromptGet> for Request {
+    fn from(get: PromptGet) -> Self {
+        Self::PromptsGet { params: get }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetLevel {
+    pub level: logging::Level,
+}
+
+impl From<SetLevel> for Request {
+    fn from(set_level: SetLevel) -> Self {
+        Self::LoggingSetLevel { params: set_level }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Complete {
+    #[serde(rename = "ref")]
+    pub reference: completion::Reference,
+    pub argument: completion::Argument,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<completion::Context>,
+}
+
+impl From<Complete> for Request {
+    fn from(complete: Complete) -> Self {
+        Self::CompletionComplete { params: complete }
+    }
+}
@@ -1,4 +1,35 @@
-use serde::Serialize;
+use crate::client::{Root, elicitation, sampling};
+
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize)]
-pub enum Response {}
+#[serde(untagged)]
+pub enum Response {
+    Sampling(sampling::Response),
+    Elicitation(elicitation::Response),
+    RootsList(RootsList),
+    Ping {},
+}
+
+impl From<sampling::Response> for Response {
+    fn from(response: sampling::Response) -> Self {
+        Self::Sampling(response)
+    }
+}
+
+impl From<elicitation::Response> for Response {
+    fn from(response: elicitation::Response) -> Self {
+        Self::Elicitation(response)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RootsList {
+    pub roots: Vec<Root>,
+}
+
+impl From<RootsList> for Response {
+    fn from(response: RootsList) -> Self {
+        Self::RootsList(response)
+    }
+}
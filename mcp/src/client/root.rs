@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Root {
+    pub uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Root {
+    pub fn new(uri: impl AsRef<str>) -> Self {
+        Self {
+            uri: uri.as_ref().to_owned(),
+            name: None,
+        }
+    }
+
+    pub fn name(self, name: impl AsRef<str>) -> Self {
+        Self {
+            name: Some(name.as_ref().to_owned()),
+            ..self
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+
+/// The maximum amount of values in a [`Completion`].
+pub const MAX_VALUES: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Reference {
+    #[serde(rename = "ref/prompt")]
+    Prompt { name: String },
+    #[serde(rename = "ref/resource")]
+    Resource { uri: String },
+}
+
+impl Reference {
+    pub fn prompt(name: impl AsRef<str>) -> Self {
+        Self::Prompt {
+            name: name.as_ref().to_owned(),
+        }
+    }
+
+    pub fn resource(uri: impl AsRef<str>) -> Self {
+        Self::Resource {
+            uri: uri.as_ref().to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Argument {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Context {
+    #[serde(default)]
+    pub arguments: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Completion {
+    pub values: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_more: Option<bool>,
+}
+
+impl Completion {
+    pub fn new(values: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        let mut values: Vec<_> = values
+            .into_iter()
+            .map(|value| value.as_ref().to_owned())
+            .collect();
+
+        if values.len() <= MAX_VALUES {
+            return Self {
+                values,
+                total: None,
+                has_more: None,
+            };
+        }
+
+        let total = values.len();
+        values.truncate(MAX_VALUES);
+
+        Self {
+            values,
+            total: Some(total),
+            has_more: Some(true),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn few_values_are_complete() {
+        let completion = Completion::new(["a", "b"]);
+
+        assert_eq!(completion.values, ["a", "b"]);
+        assert_eq!(completion.total, None);
+        assert_eq!(completion.has_more, None);
+    }
+
+    #[test]
+    fn many_values_are_truncated() {
+        let completion = Completion::new((0..=MAX_VALUES).map(|value| value.to_string()));
+
+        assert_eq!(completion.values.len(), MAX_VALUES);
+        assert_eq!(completion.values.last().map(String::as_str), Some("99"));
+        assert_eq!(completion.total, Some(MAX_VALUES + 1));
+        assert_eq!(completion.has_more, Some(true));
+    }
+}
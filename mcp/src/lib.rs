@@ -1,9 +1,17 @@
 pub mod client;
+pub mod completion;
+pub mod logging;
+pub mod progress;
+pub mod schematic;
 pub mod server;
+pub mod validation;
+pub mod version;
 
 pub use client::Client;
+pub use schematic::Schematic;
 pub use server::Server;
 pub use skema::Schema;
+pub use version::Version;
 
 pub use bytes::Bytes;
 pub use serde::de::IgnoredAny as Ignored;
@@ -14,7 +22,8 @@ use serde::{Deserialize, Serialize};
 
 use std::io;
 
-pub const VERSION: &str = "2025-06-18";
+/// The latest supported version of the protocol.
+pub const VERSION: &str = Version::LATEST.as_str();
 pub const JSONRPC: &str = "2.0";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -169,16 +178,21 @@ impl Error {
     }
 
     pub fn method_not_found(method: String) -> Self {
-        Self::new(
-            None,
-            ErrorKind::new(-32601, format!("Unknown method: {method}")),
-        )
+        Self::new(None, ErrorKind::method_not_found(method))
     }
 
     pub fn invalid_json(message: String) -> Self {
         Self::new(None, ErrorKind::new(-32700, message))
     }
 
+    pub fn id(&self) -> Option<Id> {
+        self.id
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.payload
+    }
+
     pub fn serialize(&self) -> serde_json::Result<Bytes> {
         serde_json::to_vec(self).map(Bytes::from_owner)
     }
@@ -188,16 +202,62 @@ impl Error {
 pub struct ErrorKind {
     code: i64,
     message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
 }
 
 impl ErrorKind {
     fn new(code: i64, message: String) -> Self {
-        Self { code, message }
+        Self {
+            code,
+            message,
+            data: None,
+        }
+    }
+
+    /// Attaches additional information about the error.
+    pub fn with_data(self, data: Value) -> Self {
+        Self {
+            data: Some(data),
+            ..self
+        }
+    }
+
+    pub fn method_not_found(method: String) -> Self {
+        Self::new(-32601, format!("Unknown method: {method}"))
     }
 
     pub fn invalid_params(message: String) -> Self {
         Self::new(-32602, message)
     }
+
+    pub fn internal_error(message: String) -> Self {
+        Self::new(-32603, message)
+    }
+
+    pub fn resource_not_found(uri: String) -> Self {
+        Self::new(-32002, format!("Resource not found: {uri}"))
+    }
+
+    pub fn code(&self) -> i64 {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn data(&self) -> Option<&Value> {
+        self.data.as_ref()
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        io::Error::other(error)
+    }
 }
 
 impl std::fmt::Display for Error {
@@ -217,6 +277,28 @@ impl std::fmt::Display for ErrorKind {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress_token: Option<progress::Token>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cancelled {
+    pub request_id: Id,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
 pub struct Id(u64);
 
@@ -231,3 +313,7 @@ impl Id {
 pub fn from_value<T: DeserializeOwned>(value: Value) -> io::Result<T> {
     Ok(serde_json::from_value(value)?)
 }
+
+pub fn to_value<T: Serialize>(value: T) -> io::Result<Value> {
+    Ok(serde_json::to_value(value)?)
+}
@@ -0,0 +1,147 @@
+use crate::Schema;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// A type that can describe its JSON representation with a [`Schema`].
+///
+/// It can be derived for structs with named fields and enums with unit
+/// variants with the `macros` feature of `techne`.
+pub trait Schematic {
+    fn schema() -> Schema;
+
+    /// Returns `true` if the type may be omitted when it is the property
+    /// of an object.
+    fn is_optional() -> bool {
+        false
+    }
+}
+
+/// A [`Schematic`] type described by an object schema.
+///
+/// It is implemented by the derived [`Schematic`] of structs with named
+/// fields.
+pub trait Object: Schematic {}
+
+impl<T: Object + ?Sized> Object for &T {}
+
+impl<T: Object + ?Sized> Object for Box<T> {}
+
+impl<K, V> Object for BTreeMap<K, V> {}
+
+impl<K, V, S> Object for HashMap<K, V, S> {}
+
+/// Replaces the description of the given [`Schema`].
+pub fn describe(schema: Schema, description: impl AsRef<str>) -> Schema {
+    let description = Some(description.as_ref().to_owned()).filter(|text| !text.is_empty());
+
+    match schema {
+        Schema::Object {
+            properties,
+            required,
+            ..
+        } => Schema::Object {
+            description,
+            properties,
+            required,
+        },
+        Schema::String { .. } => Schema::String { description },
+        Schema::Integer { .. } => Schema::Integer { description },
+        Schema::Number { .. } => Schema::Number { description },
+        Schema::Boolean { .. } => Schema::Boolean { description },
+        Schema::Array { items, .. } => Schema::Array { description, items },
+        Schema::Null => Schema::Null,
+    }
+}
+
+macro_rules! primitive {
+    ($variant:ident: $($type:ty),+) => {
+        $(
+            impl Schematic for $type {
+                fn schema() -> Schema {
+                    Schema::$variant { description: None }
+                }
+            }
+        )+
+    };
+}
+
+primitive!(String: String, str, char);
+primitive!(Integer: u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+primitive!(Number: f32, f64);
+primitive!(Boolean: bool);
+
+impl Schematic for () {
+    fn schema() -> Schema {
+        Schema::Null
+    }
+}
+
+impl<T: Schematic + ?Sized> Schematic for &T {
+    fn schema() -> Schema {
+        T::schema()
+    }
+
+    fn is_optional() -> bool {
+        T::is_optional()
+    }
+}
+
+impl<T: Schematic + ?Sized> Schematic for Box<T> {
+    fn schema() -> Schema {
+        T::schema()
+    }
+
+    fn is_optional() -> bool {
+        T::is_optional()
+    }
+}
+
+impl<T: Schematic> Schematic for Option<T> {
+    fn schema() -> Schema {
+        T::schema()
+    }
+
+    fn is_optional() -> bool {
+        true
+    }
+}
+
+macro_rules! array {
+    ($($type:ty),+) => {
+        $(
+            impl<T: Schematic> Schematic for $type {
+                fn schema() -> Schema {
+                    Schema::Array {
+                        description: None,
+                        items: Some(Box::new(T::schema())),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+array!([T], Vec<T>, BTreeSet<T>, HashSet<T>);
+
+impl<T: Schematic, const N: usize> Schematic for [T; N] {
+    fn schema() -> Schema {
+        <[T]>::schema()
+    }
+}
+
+// Maps have arbitrary keys, which cannot be described as properties
+impl<K, V> Schematic for BTreeMap<K, V> {
+    fn schema() -> Schema {
+        Schema::Object {
+            description: None,
+            properties: BTreeMap::new(),
+            required: Vec::new(),
+        }
+    }
+}
+
+impl<K, V, S> Schematic for HashMap<K, V, S> {
+    fn schema() -> Schema {
+        BTreeMap::<K, V>::schema()
+    }
+}
@@ -1,14 +1,18 @@
 pub mod capabilities;
 pub mod content;
 pub mod notification;
+pub mod prompt;
 pub mod request;
+pub mod resource;
 pub mod response;
 pub mod tool;
 
 pub use capabilities::Capabilities;
 pub use content::Content;
 pub use notification::Notification;
+pub use prompt::Prompt;
 pub use request::Request;
+pub use resource::Resource;
 pub use response::Response;
 pub use tool::Tool;
 
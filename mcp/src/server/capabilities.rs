@@ -4,6 +4,14 @@ use serde::{Deserialize, Serialize};
 pub struct Capabilities {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Tools>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<Resources>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<Prompts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logging: Option<Logging>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completions: Option<Completions>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +21,28 @@ pub struct Tools {
     pub list_changed: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Resources {
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub subscribe: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub list_changed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Prompts {
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub list_changed: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Logging {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Completions {}
+
 fn is_false(b: &bool) -> bool {
     !b
 }
@@ -1,36 +1,77 @@
 use serde::{Deserialize, Serialize};
 
+use std::io;
+
+/// The content of a tool result.
+///
+/// Structured content should be accompanied by some unstructured
+/// content for clients that do not support it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Content<T = serde_json::Value> {
-    #[serde(rename = "content")]
-    Unstructured(Vec<Unstructured>),
-    #[serde(rename = "structuredContent")]
-    Structured(T),
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+pub struct Content<T = serde_json::Value> {
+    #[serde(rename = "content", default)]
+    pub unstructured: Vec<Unstructured>,
+    #[serde(
+        rename = "structuredContent",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub structured: Option<T>,
+}
+
+impl<T> Content<T> {
+    pub fn unstructured(content: impl IntoIterator<Item = Unstructured>) -> Self {
+        Self {
+            unstructured: content.into_iter().collect(),
+            structured: None,
+        }
+    }
+
+    pub fn structured(content: T) -> Self {
+        Self {
+            unstructured: Vec::new(),
+            structured: Some(content),
+        }
+    }
 }
 
 impl<T> From<Unstructured> for Content<T> {
     fn from(content: Unstructured) -> Self {
-        Content::Unstructured(vec![content])
+        Content::unstructured([content])
     }
 }
 
 impl<T> From<String> for Content<T> {
     fn from(text: String) -> Self {
-        Content::Unstructured(vec![Unstructured::Text { text }])
+        Content::unstructured([Unstructured::Text { text }])
     }
 }
 
 impl<T> From<u32> for Content<T> {
     fn from(number: u32) -> Self {
-        Content::Unstructured(vec![Unstructured::Text {
+        Content::unstructured([Unstructured::Text {
             text: number.to_string(),
         }])
     }
 }
 
+impl From<String> for Unstructured {
+    fn from(text: String) -> Self {
+        Unstructured::Text { text }
+    }
+}
+
+impl From<&str> for Unstructured {
+    fn from(text: &str) -> Self {
+        Unstructured::Text {
+            text: text.to_owned(),
+        }
+    }
+}
+
 impl From<serde_json::Value> for Content {
     fn from(json: serde_json::Value) -> Self {
-        Content::Structured(json)
+        Content::structured(json)
     }
 }
 
@@ -62,6 +103,142 @@ pub enum Unstructured {
     },
 }
 
-// TODO
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Base64(String);
+
+impl Base64 {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    pub fn encode(bytes: impl AsRef<[u8]>) -> Self {
+        let bytes = bytes.as_ref();
+        let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+        for chunk in bytes.chunks(3) {
+            let b = [
+                chunk[0],
+                chunk.get(1).copied().unwrap_or(0),
+                chunk.get(2).copied().unwrap_or(0),
+            ];
+
+            let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    let index = (n >> (18 - 6 * i)) & 0x3F;
+                    encoded.push(Self::ALPHABET[index as usize] as char);
+                } else {
+                    encoded.push('=');
+                }
+            }
+        }
+
+        Self(encoded)
+    }
+
+    pub fn decode(&self) -> io::Result<Vec<u8>> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let input = self.0.as_bytes();
+
+        if !input.len().is_multiple_of(4) {
+            return Err(invalid(format!("invalid base64 length: {}", input.len())));
+        }
+
+        let padding = input.iter().rev().take_while(|byte| **byte == b'=').count();
+
+        if padding > 2 {
+            return Err(invalid("invalid base64 padding".to_owned()));
+        }
+
+        let input = &input[..input.len() - padding];
+        let mut decoded = Vec::with_capacity(input.len() * 3 / 4);
+
+        let mut buffer = 0u32;
+        let mut bits = 0;
+
+        for byte in input {
+            let Some(value) = Self::ALPHABET.iter().position(|c| c == byte) else {
+                return Err(invalid(format!(
+                    "invalid base64 character: {:?}",
+                    *byte as char
+                )));
+            };
+
+            buffer = (buffer << 6) | value as u32;
+            bits += 6;
+
+            if bits >= 8 {
+                bits -= 8;
+                decoded.push((buffer >> bits) as u8);
+            }
+        }
+
+        // Padding bits must be zero
+        if buffer & ((1 << bits) - 1) != 0 {
+            return Err(invalid("invalid base64 padding".to_owned()));
+        }
+
+        Ok(decoded)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+
+        for length in 0..bytes.len() {
+            let encoded = Base64::encode(&bytes[..length]);
+
+            assert!(encoded.as_str().len().is_multiple_of(4));
+            assert_eq!(
+                encoded.decode().expect("base64 must be valid"),
+                &bytes[..length]
+            );
+        }
+    }
+
+    #[test]
+    fn base64_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+
+        for (text, encoded) in vectors {
+            assert_eq!(Base64::encode(text).as_str(), encoded);
+            assert_eq!(
+                Base64(encoded.to_owned())
+                    .decode()
+                    .expect("base64 must be valid"),
+                text.as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn base64_invalid() {
+        let invalid = [
+            "A", "Zg", "Zg=", "Zm9vY", "Z===", "====", "Zg=a", "Zg==Zg==", "Zm9!", "Zh==", "Zm9=",
+        ];
+
+        for encoded in invalid {
+            assert!(
+                Base64(encoded.to_owned()).decode().is_err(),
+                "{encoded:?} must be invalid"
+            );
+        }
+    }
+}
@@ -1,4 +1,47 @@
+use crate::{Cancelled, logging, progress};
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Notification {}
+#[serde(tag = "method")]
+pub enum Notification {
+    #[serde(rename = "notifications/cancelled")]
+    Cancelled { params: Cancelled },
+    #[serde(rename = "notifications/tools/list_changed")]
+    ToolsListChanged,
+    #[serde(rename = "notifications/resources/updated")]
+    ResourcesUpdated { params: ResourceUpdated },
+    #[serde(rename = "notifications/progress")]
+    Progress { params: progress::Progress },
+    #[serde(rename = "notifications/message")]
+    LoggingMessage { params: logging::Message },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceUpdated {
+    pub uri: String,
+}
+
+impl From<ResourceUpdated> for Notification {
+    fn from(updated: ResourceUpdated) -> Self {
+        Self::ResourcesUpdated { params: updated }
+    }
+}
+
+impl From<progress::Progress> for Notification {
+    fn from(progress: progress::Progress) -> Self {
+        Self::Progress { params: progress }
+    }
+}
+
+impl From<logging::Message> for Notification {
+    fn from(message: logging::Message) -> Self {
+        Self::LoggingMessage { params: message }
+    }
+}
+
+impl From<Cancelled> for Notification {
+    fn from(cancelled: Cancelled) -> Self {
+        Self::Cancelled { params: cancelled }
+    }
+}
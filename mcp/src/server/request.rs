@@ -1,4 +1,91 @@
+use crate::client::elicitation;
+use crate::client::sampling::{self, IncludeContext, ModelPreferences};
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Request {}
+#[serde(tag = "method")]
+pub enum Request {
+    #[serde(rename = "ping")]
+    Ping,
+    #[serde(rename = "sampling/createMessage")]
+    SamplingCreateMessage { params: CreateMessage },
+    #[serde(rename = "elicitation/create")]
+    ElicitationCreate { params: CreateElicitation },
+    #[serde(rename = "roots/list")]
+    RootsList,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessage {
+    pub messages: Vec<sampling::Message>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_preferences: Option<ModelPreferences>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_context: Option<IncludeContext>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    pub max_tokens: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl CreateMessage {
+    pub fn new(messages: impl IntoIterator<Item = sampling::Message>, max_tokens: u32) -> Self {
+        Self {
+            messages: messages.into_iter().collect(),
+            model_preferences: None,
+            system_prompt: None,
+            include_context: None,
+            temperature: None,
+            max_tokens,
+            stop_sequences: Vec::new(),
+            metadata: None,
+        }
+    }
+
+    pub fn system_prompt(self, system_prompt: impl AsRef<str>) -> Self {
+        Self {
+            system_prompt: Some(system_prompt.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    pub fn temperature(self, temperature: f32) -> Self {
+        Self {
+            temperature: Some(temperature),
+            ..self
+        }
+    }
+
+    pub fn model_preferences(self, model_preferences: ModelPreferences) -> Self {
+        Self {
+            model_preferences: Some(model_preferences),
+            ..self
+        }
+    }
+}
+
+impl From<CreateMessage> for Request {
+    fn from(create: CreateMessage) -> Self {
+        Self::SamplingCreateMessage { params: create }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateElicitation {
+    pub message: String,
+    pub requested_schema: elicitation::Schema,
+}
+
+impl From<CreateElicitation> for Request {
+    fn from(create: CreateElicitation) -> Self {
+        Self::ElicitationCreate { params: create }
+    }
+}
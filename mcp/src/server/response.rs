@@ -1,3 +1,6 @@
+use crate::completion::Completion;
+use crate::server::prompt::{self, Prompt};
+use crate::server::resource::{self, Resource};
 use crate::server::tool;
 use crate::server::{Capabilities, Server, Tool};
 
@@ -9,7 +12,14 @@ pub enum Response {
     Initialize(Initialize),
     ToolsList(ToolsList),
     ToolsCall(tool::Response),
+    ResourcesList(ResourcesList),
+    ResourceTemplatesList(ResourceTemplatesList),
+    ResourcesRead(ResourcesRead),
+    PromptsList(PromptsList),
+    PromptsGet(PromptsGet),
+    Complete(Complete),
     Ping {},
+    Empty {},
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,8 +37,11 @@ impl From<Initialize> for Response {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ToolsList {
     pub tools: Vec<Tool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 impl From<ToolsList> for Response {
@@ -42,3 +55,80 @@ impl From<tool::Response> for Response {
         Self::ToolsCall(response)
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesList {
+    pub resources: Vec<Resource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl From<ResourcesList> for Response {
+    fn from(response: ResourcesList) -> Self {
+        Self::ResourcesList(response)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceTemplatesList {
+    pub resource_templates: Vec<resource::Template>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl From<ResourceTemplatesList> for Response {
+    fn from(response: ResourceTemplatesList) -> Self {
+        Self::ResourceTemplatesList(response)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourcesRead {
+    pub contents: Vec<resource::Contents>,
+}
+
+impl From<ResourcesRead> for Response {
+    fn from(response: ResourcesRead) -> Self {
+        Self::ResourcesRead(response)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptsList {
+    pub prompts: Vec<Prompt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl From<PromptsList> for Response {
+    fn from(response: PromptsList) -> Self {
+        Self::PromptsList(response)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptsGet {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub messages: Vec<prompt::Message>,
+}
+
+impl From<PromptsGet> for Response {
+    fn from(response: PromptsGet) -> Self {
+        Self::PromptsGet(response)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Complete {
+    pub completion: Completion,
+}
+
+impl From<Complete> for Response {
+    fn from(response: Complete) -> Self {
+        Self::Complete(response)
+    }
+}
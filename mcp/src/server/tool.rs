@@ -1,5 +1,7 @@
 use crate::Schema;
+use crate::schematic::Object;
 use crate::server::content::{self, Content};
+use crate::validation;
 
 use serde::{Deserialize, Serialize};
 
@@ -23,17 +25,56 @@ pub struct Response<T = serde_json::Value> {
     pub is_error: bool,
 }
 
+impl Response {
+    /// Validates the structured content of the [`Response`] against the
+    /// output schema of its tool.
+    ///
+    /// Error responses are always valid.
+    pub fn validate(&self, output_schema: &Schema) -> Result<(), validation::Error> {
+        if self.is_error {
+            return Ok(());
+        }
+
+        let Some(content) = &self.content.structured else {
+            return Err(validation::Error {
+                violations: vec![validation::Violation {
+                    pointer: String::new(),
+                    message: "missing structured content".to_owned(),
+                }],
+            });
+        };
+
+        validation::validate(output_schema, content)
+    }
+}
+
 impl<T> Response<T> {
+    /// Serializes the structured content of the [`Response`], adding
+    /// it as text when there is no unstructured content.
     pub async fn serialize(self) -> serde_json::Result<Response>
     where
         T: Serialize,
     {
+        let structured = self
+            .content
+            .structured
+            .map(|content| serde_json::to_value(&content))
+            .transpose()?;
+
+        let mut unstructured = self.content.unstructured;
+
+        if unstructured.is_empty()
+            && let Some(content) = &structured
+        {
+            unstructured.push(content::Unstructured::Text {
+                text: serde_json::to_string(content)?,
+            });
+        }
+
         Ok(Response {
-            content: match self.content {
-                Content::Unstructured(content) => Content::Unstructured(content),
-                Content::Structured(content) => {
-                    Content::Structured(serde_json::to_value(&content)?)
-                }
+            content: Content {
+                unstructured,
+                structured,
             },
             is_error: self.is_error,
         })
@@ -44,6 +85,11 @@ pub trait IntoResponse {
     type Content;
 
     fn into_outcome(self) -> Response<Self::Content>;
+
+    /// Returns the schema of the structured content of the response, if known.
+    fn output_schema() -> Option<Schema> {
+        None
+    }
 }
 
 impl<T> IntoResponse for T
@@ -62,23 +108,49 @@ where
 
 impl<T, E> IntoResponse for Result<T, E>
 where
-    T: Into<Content<T>>,
+    T: IntoResponse,
     E: std::error::Error,
 {
-    type Content = T;
+    type Content = T::Content;
 
-    fn into_outcome(self) -> Response<T> {
+    fn into_outcome(self) -> Response<T::Content> {
         match self {
-            Ok(value) => Response {
-                content: value.into(),
-                is_error: false,
-            },
+            Ok(value) => value.into_outcome(),
             Err(error) => Response {
-                content: Content::Unstructured(vec![content::Unstructured::Text {
+                content: Content::unstructured([content::Unstructured::Text {
                     text: error.to_string(),
                 }]),
                 is_error: true,
             },
         }
     }
+
+    fn output_schema() -> Option<Schema> {
+        T::output_schema()
+    }
+}
+
+/// The structured output of a tool.
+///
+/// Its [`Schematic`] is advertised as the output schema of the tool, which
+/// must describe an [`Object`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Structured<T>(pub T);
+
+impl<T> IntoResponse for Structured<T>
+where
+    T: Object,
+{
+    type Content = T;
+
+    fn into_outcome(self) -> Response<T> {
+        Response {
+            content: Content::structured(self.0),
+            is_error: false,
+        }
+    }
+
+    fn output_schema() -> Option<Schema> {
+        Some(T::schema())
+    }
 }
@@ -0,0 +1,302 @@
+use crate::{Schema, Value};
+
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+use std::io;
+
+/// A part of a JSON value that does not match its [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Violation {
+    /// The JSON pointer of the offending value.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{pointer:?}: {message}",
+            pointer = self.pointer,
+            message = self.message
+        )
+    }
+}
+
+/// A JSON value that does not match its [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Error {
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "value does not match its schema")?;
+
+        for violation in &self.violations {
+            write!(f, "\n- {violation}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+/// Validates a JSON value against a [`Schema`], returning every violation found.
+///
+/// Properties that are not required may be `null`, since a [`Schema`]
+/// cannot describe nullable values.
+pub fn validate(schema: &Schema, value: &Value) -> Result<(), Error> {
+    let mut violations = Vec::new();
+
+    visit(schema, value, &mut String::new(), &mut violations);
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(Error { violations })
+    }
+}
+
+fn visit(schema: &Schema, value: &Value, pointer: &mut String, violations: &mut Vec<Violation>) {
+    let mut violation = |expected: &str| {
+        violations.push(Violation {
+            pointer: pointer.clone(),
+            message: format!("expected {expected}, found {}", kind(value)),
+        });
+    };
+
+    match schema {
+        Schema::Object {
+            properties,
+            required,
+            ..
+        } => {
+            let Value::Object(object) = value else {
+                return violation("an object");
+            };
+
+            for name in required {
+                if !object.contains_key(name) {
+                    violations.push(Violation {
+                        pointer: pointer.clone(),
+                        message: format!("missing required property {name:?}"),
+                    });
+                }
+            }
+
+            for (name, schema) in properties {
+                let Some(value) = object.get(name) else {
+                    continue;
+                };
+
+                if value.is_null() && !required.contains(name) {
+                    continue;
+                }
+
+                let length = pointer.len();
+                push(pointer, name);
+                visit(schema, value, pointer, violations);
+                pointer.truncate(length);
+            }
+        }
+        Schema::String { .. } => {
+            if !value.is_string() {
+                violation("a string");
+            }
+        }
+        Schema::Integer { .. } => {
+            let is_integer = value.is_i64()
+                || value.is_u64()
+                || value.as_f64().is_some_and(|number| number.fract() == 0.0);
+
+            if !is_integer {
+                violation("an integer");
+            }
+        }
+        Schema::Number { .. } => {
+            if !value.is_number() {
+                violation("a number");
+            }
+        }
+        Schema::Boolean { .. } => {
+            if !value.is_boolean() {
+                violation("a boolean");
+            }
+        }
+        Schema::Array { items, .. } => {
+            let Value::Array(values) = value else {
+                return violation("an array");
+            };
+
+            let Some(items) = items else {
+                return;
+            };
+
+            for (i, value) in values.iter().enumerate() {
+                let length = pointer.len();
+                push(pointer, &i.to_string());
+                visit(items, value, pointer, violations);
+                pointer.truncate(length);
+            }
+        }
+        Schema::Null => {
+            if !value.is_null() {
+                violation("null");
+            }
+        }
+    }
+}
+
+fn push(pointer: &mut String, token: &str) {
+    pointer.push('/');
+    pointer.push_str(&token.replace('~', "~0").replace('/', "~1"));
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    fn schema(schema: Value) -> Schema {
+        serde_json::from_value(schema).expect("schema must be valid")
+    }
+
+    fn pointers(result: Result<(), Error>) -> Vec<String> {
+        result
+            .expect_err("value must be invalid")
+            .violations
+            .into_iter()
+            .map(|violation| violation.pointer)
+            .collect()
+    }
+
+    #[test]
+    fn valid_values() {
+        let schema = schema(json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer" },
+                "score": { "type": "number" },
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "nickname": { "type": "string" }
+            },
+            "required": ["name", "age"]
+        }));
+
+        let value = json!({
+            "name": "Ferris",
+            "age": 8.0,
+            "score": 1.5,
+            "tags": ["crab"],
+            "nickname": null
+        });
+
+        assert_eq!(validate(&schema, &value), Ok(()));
+    }
+
+    #[test]
+    fn type_mismatches() {
+        let cases = [
+            (json!({ "type": "string" }), json!(1)),
+            (json!({ "type": "integer" }), json!(1.5)),
+            (json!({ "type": "number" }), json!("1")),
+            (json!({ "type": "boolean" }), json!(0)),
+            (json!({ "type": "array" }), json!({})),
+            (json!({ "type": "object", "properties": {} }), json!([])),
+            (json!({ "type": "null" }), json!(false)),
+        ];
+
+        for (expected, value) in cases {
+            assert_eq!(pointers(validate(&schema(expected), &value)), [""]);
+        }
+    }
+
+    #[test]
+    fn missing_required_properties() {
+        let schema = schema(json!({
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+        }));
+
+        let error = validate(&schema, &json!({})).expect_err("value must be invalid");
+
+        assert_eq!(
+            error.violations,
+            [Violation {
+                pointer: String::new(),
+                message: "missing required property \"name\"".to_owned(),
+            }]
+        );
+
+        assert_eq!(
+            pointers(validate(&schema, &json!({ "name": null }))),
+            ["/name"]
+        );
+    }
+
+    #[test]
+    fn nested_pointers() {
+        let schema = schema(json!({
+            "type": "object",
+            "properties": {
+                "people": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "age": { "type": "integer" } }
+                    }
+                }
+            }
+        }));
+
+        let value = json!({
+            "people": [{ "age": 1 }, { "age": "two" }, { "age": 3 }, { "age": false }]
+        });
+
+        assert_eq!(
+            pointers(validate(&schema, &value)),
+            ["/people/1/age", "/people/3/age"]
+        );
+    }
+
+    #[test]
+    fn pointer_escaping() {
+        let schema = schema(json!({
+            "type": "object",
+            "properties": {
+                "a/b": { "type": "string" },
+                "m~n": { "type": "string" },
+                "~/": { "type": "string" }
+            }
+        }));
+
+        let value = json!({ "a/b": 1, "m~n": 2, "~/": 3 });
+
+        assert_eq!(
+            pointers(validate(&schema, &value)),
+            ["/a~1b", "/m~0n", "/~0~1"]
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A version of the protocol.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Version {
+    V2025_03_26,
+    #[default]
+    V2025_06_18,
+}
+
+impl Version {
+    /// All the supported versions, from oldest to newest.
+    pub const ALL: &[Self] = &[Self::V2025_03_26, Self::V2025_06_18];
+
+    pub const LATEST: Self = Self::V2025_06_18;
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::V2025_03_26 => "2025-03-26",
+            Self::V2025_06_18 => "2025-06-18",
+        }
+    }
+
+    /// Chooses the version to use given the one requested by a client.
+    ///
+    /// The requested version is used if supported; otherwise, the latest
+    /// supported version is proposed instead.
+    pub fn negotiate(requested: &str) -> Self {
+        requested.parse().unwrap_or(Self::LATEST)
+    }
+
+    /// Returns `true` if tools can have an output schema and return
+    /// structured content.
+    pub fn supports_structured_output(self) -> bool {
+        self >= Self::V2025_06_18
+    }
+
+    /// Returns `true` if servers can elicit information from clients.
+    pub fn supports_elicitation(self) -> bool {
+        self >= Self::V2025_06_18
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Version {
+    type Err = UnsupportedVersion;
+
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|candidate| candidate.as_str() == version)
+            .ok_or_else(|| UnsupportedVersion(version.to_owned()))
+    }
+}
+
+/// The error of parsing an unknown or unsupported [`Version`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedVersion(pub String);
+
+impl fmt::Display for UnsupportedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unsupported protocol version {given:?} (supported: {supported})",
+            given = self.0,
+            supported = Version::ALL
+                .iter()
+                .map(|version| version.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedVersion {}
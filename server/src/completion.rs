@@ -0,0 +1,133 @@
+use crate::mcp::completion::Completion;
+
+use futures::FutureExt;
+use futures::future::BoxFuture;
+
+use std::collections::BTreeMap;
+use std::io;
+
+pub trait IntoCompletion {
+    fn into_completion(self) -> io::Result<Completion>;
+}
+
+impl IntoCompletion for Completion {
+    fn into_completion(self) -> io::Result<Completion> {
+        Ok(self)
+    }
+}
+
+impl IntoCompletion for Vec<String> {
+    fn into_completion(self) -> io::Result<Completion> {
+        Ok(Completion::new(self))
+    }
+}
+
+impl IntoCompletion for Vec<&'static str> {
+    fn into_completion(self) -> io::Result<Completion> {
+        Ok(Completion::new(self))
+    }
+}
+
+impl<T, E> IntoCompletion for Result<T, E>
+where
+    T: IntoCompletion,
+    E: std::error::Error,
+{
+    fn into_completion(self) -> io::Result<Completion> {
+        match self {
+            Ok(completion) => completion.into_completion(),
+            Err(error) => Err(io::Error::other(error.to_string())),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Completions {
+    providers: BTreeMap<String, Box<Complete>>,
+}
+
+impl Completions {
+    pub fn insert<T, F>(
+        &mut self,
+        argument: impl AsRef<str>,
+        complete: impl Fn(String) -> F + Send + Sync + 'static,
+    ) where
+        T: IntoCompletion + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        let _ = self.providers.insert(
+            argument.as_ref().to_owned(),
+            Box::new(move |value| complete(value).map(IntoCompletion::into_completion).boxed()),
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    pub async fn complete(&self, argument: &str, value: String) -> io::Result<Completion> {
+        let Some(complete) = self.providers.get(argument) else {
+            return Ok(Completion::default());
+        };
+
+        complete(value).await
+    }
+}
+
+type Complete = dyn Fn(String) -> BoxFuture<'static, io::Result<Completion>> + Send + Sync;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completions() -> Completions {
+        let mut completions = Completions::default();
+
+        completions.insert("color", async |value: String| {
+            ["red", "green", "blue"]
+                .into_iter()
+                .filter(|color| color.starts_with(&value))
+                .collect::<Vec<_>>()
+        });
+
+        completions.insert("size", async |_value| {
+            Err::<Vec<String>, _>(io::Error::other("sizes are unknown"))
+        });
+
+        completions
+    }
+
+    #[tokio::test]
+    async fn values_are_completed() {
+        let completions = completions();
+
+        let completion = completions
+            .complete("color", "gr".to_owned())
+            .await
+            .expect("completion must succeed");
+
+        assert_eq!(completion.values, ["green"]);
+        assert!(!completions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unknown_arguments_have_no_values() {
+        let completion = completions()
+            .complete("shape", "ci".to_owned())
+            .await
+            .expect("completion must succeed");
+
+        assert!(completion.values.is_empty());
+        assert!(Completions::default().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_completions() {
+        let error = completions()
+            .complete("size", String::new())
+            .await
+            .expect_err("completion must fail");
+
+        assert_eq!(error.to_string(), "sizes are unknown");
+    }
+}
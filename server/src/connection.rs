@@ -1,39 +1,38 @@
 use crate::mcp;
 use crate::mcp::server::{Notification, Request, Response};
 use crate::mcp::{Bytes, ErrorKind, Id};
+use crate::session::{Session, Stream};
 use crate::transport::{Channel, Result};
 
-use futures::SinkExt;
-use futures::channel::mpsc;
-
 use std::io;
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub(crate) struct Connection {
     id: Id,
+    session: Arc<Session>,
     state: State,
-    total_requests: Id,
 }
 
 impl Connection {
-    pub fn new(id: Id, channel: Channel) -> Self {
+    pub fn new(id: Id, channel: Channel, session: Arc<Session>) -> Self {
         Self {
             id,
+            session,
             state: State::Idle(channel),
-            total_requests: Id::default(),
         }
     }
 
-    pub async fn request(&mut self, request: Request) -> io::Result<()> {
-        let id = self.total_requests.increment();
+    pub fn id(&self) -> Id {
+        self.id
+    }
 
-        #[allow(unreachable_code)]
+    pub async fn request(&mut self, id: Id, request: Request) -> io::Result<()> {
         self.stream(mcp::Request::new(id, request).serialize()?)
             .await
     }
 
     pub async fn notify(&mut self, notification: Notification) -> io::Result<()> {
-        #[allow(unreachable_code)]
         self.stream(mcp::Notification::new(notification).serialize()?)
             .await
     }
@@ -52,11 +51,35 @@ impl Connection {
             .await
     }
 
+    /// Finishes the connection that started the session with the given id.
+    pub async fn start(self, session: String, result: impl Into<Response>) -> io::Result<()> {
+        let bytes = mcp::Response::new(self.id, result.into()).serialize()?;
+
+        match self.state {
+            State::Idle(sender) => {
+                let _ = sender.send(Result::Start(session, bytes));
+            }
+            State::Streaming(stream) => {
+                stream.send(bytes).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel(self) -> io::Result<()> {
+        if let State::Idle(sender) = self.state {
+            let _ = sender.send(Result::Accept);
+        }
+
+        Ok(())
+    }
+
     pub async fn stream(&mut self, bytes: Bytes) -> io::Result<()> {
         match &mut self.state {
             State::Idle(_) => {
-                let (mut stream, receiver) = mpsc::channel(10);
-                let _ = stream.send(bytes).await;
+                let (stream, receiver) = self.session.open();
+                stream.send(bytes).await;
 
                 if let State::Idle(sender) =
                     std::mem::replace(&mut self.state, State::Streaming(stream))
@@ -64,8 +87,8 @@ impl Connection {
                     let _ = sender.send(Result::Stream(receiver));
                 }
             }
-            State::Streaming(sender) => {
-                let _ = sender.send(bytes).await;
+            State::Streaming(stream) => {
+                stream.send(bytes).await;
             }
         }
 
@@ -77,8 +100,8 @@ impl Connection {
             State::Idle(sender) => {
                 let _ = sender.send(Result::Send(bytes));
             }
-            State::Streaming(mut sender) => {
-                let _ = sender.send(bytes).await;
+            State::Streaming(stream) => {
+                stream.send(bytes).await;
             }
         }
 
@@ -89,7 +112,7 @@ impl Connection {
 #[derive(Debug)]
 enum State {
     Idle(Channel),
-    Streaming(mpsc::Sender<Bytes>),
+    Streaming(Stream),
 }
 
 #[derive(Debug)]
@@ -102,7 +125,6 @@ impl Receipt {
         Self { channel }
     }
 
-    #[allow(dead_code)]
     pub fn accept(self) {
         let _ = self.channel.send(Result::Accept);
     }
@@ -0,0 +1,171 @@
+use crate::Logger;
+use crate::mcp::server::{Notification, Request, notification};
+use crate::session::Sessions;
+use crate::tool::{self, Tool};
+
+use futures::future;
+
+use std::io;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct Handle {
+    sessions: Arc<Sessions>,
+    tools: Arc<tool::Registry>,
+}
+
+impl Handle {
+    pub(crate) fn new(sessions: Arc<Sessions>, tools: Arc<tool::Registry>) -> Self {
+        Self { sessions, tools }
+    }
+
+    /// Adds a tool to the running server, replacing any tool with the same name.
+    pub fn add_tool(&self, tool: Tool) -> io::Result<()> {
+        self.tools.insert(tool);
+        self.sessions.notify(Notification::ToolsListChanged)
+    }
+
+    /// Removes the tool with the given name from the running server.
+    ///
+    /// Returns `false` if there was no such tool.
+    pub fn remove_tool(&self, name: impl AsRef<str>) -> io::Result<bool> {
+        if !self.tools.remove(name.as_ref()) {
+            return Ok(false);
+        }
+
+        self.sessions.notify(Notification::ToolsListChanged)?;
+
+        Ok(true)
+    }
+
+    /// Replaces all the tools of the running server.
+    pub fn set_tools(&self, tools: impl IntoIterator<Item = Tool>) -> io::Result<()> {
+        self.tools.replace(tools);
+        self.sessions.notify(Notification::ToolsListChanged)
+    }
+
+    /// Returns a [`Logger`] that forwards `log` records to the client.
+    pub fn logger(&self) -> Logger {
+        Logger::new(self.sessions.clone())
+    }
+
+    /// Notifies every session subscribed to the resource with the given URI
+    /// that it has been updated.
+    pub fn notify_resource_updated(&self, uri: impl AsRef<str>) -> io::Result<()> {
+        let uri = uri.as_ref();
+
+        for session in self.sessions.all() {
+            if !session.is_subscribed(uri) {
+                continue;
+            }
+
+            session.try_notify(notification::ResourceUpdated {
+                uri: uri.to_owned(),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Pings every client listening to the server and waits for their
+    /// responses.
+    pub async fn ping(&self) -> io::Result<()> {
+        let pings = self
+            .sessions
+            .all()
+            .into_iter()
+            .filter(|session| session.is_listening())
+            .map(async |session| session.send(Request::Ping).await.map(|_| ()));
+
+        let _ = future::try_join_all(pings).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::Value;
+    use crate::transport;
+
+    use futures::StreamExt;
+    use futures::channel::mpsc;
+
+    fn echo(name: &str) -> Tool {
+        tool::tool(async |text: String| text, tool::string("text", "The text"))
+            .name(name)
+            .description("Echoes the text")
+    }
+
+    /// Returns the notifications sent to a listener.
+    async fn notifications(listener: mpsc::Receiver<transport::Event>) -> Vec<Value> {
+        listener
+            .filter_map(async |event| event.message)
+            .map(|bytes| serde_json::from_slice(&bytes).expect("notification must be valid JSON"))
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn tools_changes_are_notified() {
+        let sessions = Arc::new(Sessions::default());
+        let (id, session) = sessions.start().expect("session must start");
+        let listener = session.listen(None);
+
+        let tools = Arc::new(tool::Registry::default());
+        let handle = Handle::new(sessions.clone(), tools.clone());
+
+        handle.add_tool(echo("a")).expect("tool must be added");
+        handle.add_tool(echo("b")).expect("tool must be added");
+        assert!(handle.remove_tool("a").expect("tool must be removed"));
+        assert!(!handle.remove_tool("a").expect("tool must be removed"));
+        handle
+            .set_tools([echo("c"), echo("d")])
+            .expect("tools must be replaced");
+
+        let names: Vec<_> = tools.list().iter().map(|tool| tool.name.clone()).collect();
+        assert_eq!(names, ["c", "d"]);
+
+        let _ = sessions.end(&id);
+        let notifications = notifications(listener).await;
+
+        assert_eq!(notifications.len(), 4);
+        assert!(
+            notifications
+                .iter()
+                .all(|notification| notification["method"] == "notifications/tools/list_changed")
+        );
+    }
+
+    #[tokio::test]
+    async fn resource_updates_are_notified_to_subscribers() {
+        let sessions = Arc::new(Sessions::default());
+        let (subscriber_id, subscriber) = sessions.start().expect("session must start");
+        let (other_id, other) = sessions.start().expect("session must start");
+
+        let subscriber_listener = subscriber.listen(None);
+        let other_listener = other.listen(None);
+
+        subscriber.subscribe("file:///a".to_owned());
+
+        let handle = Handle::new(sessions.clone(), Arc::default());
+
+        handle
+            .notify_resource_updated("file:///a")
+            .expect("update must be notified");
+        handle
+            .notify_resource_updated("file:///b")
+            .expect("update must be notified");
+
+        let _ = sessions.end(&subscriber_id);
+        let _ = sessions.end(&other_id);
+
+        let updates = notifications(subscriber_listener).await;
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0]["method"], "notifications/resources/updated");
+        assert_eq!(updates[0]["params"]["uri"], "file:///a");
+        assert!(notifications(other_listener).await.is_empty());
+    }
+}
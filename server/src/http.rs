@@ -1,4 +1,5 @@
-use crate::transport::{self, Action, Transport};
+use crate::mcp;
+use crate::transport::{self, Action, Event, Session, Transport};
 
 use futures::channel::mpsc;
 use futures::channel::oneshot;
@@ -77,10 +78,40 @@ async fn serve(
     request: hyper::Request<Incoming>,
     mut actions: mpsc::Sender<io::Result<Action>>,
 ) -> Result<Response, hyper::Error> {
+    let version = match request.headers().get(PROTOCOL_VERSION) {
+        Some(version) => match version
+            .to_str()
+            .ok()
+            .and_then(|version| version.parse::<mcp::Version>().ok())
+        {
+            Some(version) => Some(version),
+            None => return Ok(bad_request()),
+        },
+        None => None,
+    };
+
+    let session = match request.headers().get(SESSION_ID) {
+        Some(id) => match id.to_str() {
+            Ok(id) => Session::Id(id.to_owned(), version),
+            Err(_) => return Ok(bad_request()),
+        },
+        None => Session::None,
+    };
+
     Ok(match (request.method(), request.uri().path()) {
         (&http::Method::GET, "/") => {
+            let last_event = match request.headers().get(LAST_EVENT_ID) {
+                Some(id) => match id.to_str().ok().and_then(|id| id.parse().ok()) {
+                    Some(id) => Some(id),
+                    None => return Ok(bad_request()),
+                },
+                None => None,
+            };
+
             let (sender, result) = oneshot::channel();
-            let _ = actions.send(Ok(Action::Subscribe(sender))).await;
+            let _ = actions
+                .send(Ok(Action::Subscribe(session, last_event, sender)))
+                .await;
 
             handle(result).await
         }
@@ -88,7 +119,19 @@ async fn serve(
             let bytes = request.into_body().collect().await?.to_bytes();
 
             let (sender, result) = oneshot::channel();
-            let _ = actions.send(Ok(Action::Handle(bytes, sender))).await;
+            let _ = actions
+                .send(Ok(Action::Handle(session, bytes, sender)))
+                .await;
+
+            handle(result).await
+        }
+        (&http::Method::DELETE, "/") => {
+            let Session::Id(id, _) = session else {
+                return Ok(bad_request());
+            };
+
+            let (sender, result) = oneshot::channel();
+            let _ = actions.send(Ok(Action::End(id, sender))).await;
 
             handle(result).await
         }
@@ -106,7 +149,21 @@ async fn handle(result: oneshot::Receiver<transport::Result>) -> Response {
         transport::Result::Reject => bad_request(),
         transport::Result::Send(message) => ok(message),
         transport::Result::Stream(messages) => stream(messages),
+        transport::Result::Start(session, message) => {
+            let mut response = ok(message);
+
+            match HeaderValue::from_str(&session) {
+                Ok(session) => {
+                    let _ = response.headers_mut().insert(SESSION_ID, session);
+                }
+                Err(_) => return internal_error(),
+            }
+
+            response
+        }
+        transport::Result::NotFound => not_found(),
         transport::Result::Unsupported => status(StatusCode::METHOD_NOT_ALLOWED),
+        transport::Result::Unavailable => status(StatusCode::SERVICE_UNAVAILABLE),
     }
 }
 
@@ -129,16 +186,23 @@ fn ok(bytes: Bytes) -> Response {
     response
 }
 
-fn stream(stream: impl Stream<Item = Bytes> + Send + Sync + 'static) -> Response {
+fn stream(stream: impl Stream<Item = Event> + Send + Sync + 'static) -> Response {
     let mut response = Response::new(BoxBody::new(StreamBody::new(
         stream
-            .flat_map(|bytes| {
-                stream::iter([
-                    Bytes::from_static(b"data:"),
-                    bytes,
-                    Bytes::from_static(b"\n\n"),
-                ])
-                .map(Frame::data)
+            .flat_map(|event| {
+                let mut lines = vec![Bytes::from(format!("id: {}\n", event.id))];
+
+                if let Some(message) = event.message {
+                    lines.extend([
+                        Bytes::from_static(b"data: "),
+                        message,
+                        Bytes::from_static(b"\n"),
+                    ]);
+                }
+
+                lines.push(Bytes::from_static(b"\n"));
+
+                stream::iter(lines).map(Frame::data)
             })
             .map(Ok),
     )));
@@ -171,4 +235,8 @@ fn status(code: StatusCode) -> Response {
     response
 }
 
+const SESSION_ID: &str = "mcp-session-id";
+const LAST_EVENT_ID: &str = "last-event-id";
+const PROTOCOL_VERSION: &str = "mcp-protocol-version";
+
 type Response = hyper::Response<BoxBody<Bytes, hyper::Error>>;
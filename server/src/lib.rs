@@ -1,25 +1,40 @@
 pub use techne_mcp as mcp;
 
+pub mod completion;
+pub mod prompt;
+pub mod resource;
 pub mod tool;
 pub mod transport;
 
 mod connection;
+mod handle;
 #[cfg(feature = "http")]
 mod http;
+mod logger;
+mod session;
 mod stdio;
 
+pub use handle::Handle;
 #[cfg(feature = "http")]
 pub use http::Http;
+pub use logger::Logger;
+pub use prompt::Prompt;
+pub use resource::Resource;
 pub use stdio::Stdio;
 pub use tool::Tool;
 pub use transport::Transport;
 
 use crate::connection::{Connection, Receipt};
 use crate::mcp::client;
+use crate::mcp::completion::Reference;
 use crate::mcp::server;
 use crate::mcp::server::response::{self, Response};
+use crate::mcp::validation;
+use crate::session::{Session, Sessions};
+use crate::tool::Call;
 use crate::transport::{Action, Channel};
 
+use futures::channel::mpsc;
 use tokio::task;
 
 use std::collections::BTreeMap;
@@ -31,7 +46,12 @@ use std::sync::Arc;
 pub struct Server {
     name: String,
     version: String,
-    tools: BTreeMap<String, Tool>,
+    tools: Arc<tool::Registry>,
+    resources: BTreeMap<String, Resource>,
+    templates: Vec<resource::Template>,
+    prompts: BTreeMap<String, Prompt>,
+    page_size: Option<usize>,
+    sessions: Arc<Sessions>,
 }
 
 impl Server {
@@ -39,19 +59,60 @@ impl Server {
         Self {
             name: name.as_ref().to_owned(),
             version: version.as_ref().to_owned(),
-            tools: BTreeMap::new(),
+            tools: Arc::default(),
+            resources: BTreeMap::new(),
+            templates: Vec::new(),
+            prompts: BTreeMap::new(),
+            page_size: None,
+            sessions: Arc::new(Sessions::default()),
         }
     }
 
-    pub fn tools(mut self, tools: impl IntoIterator<Item = Tool>) -> Self {
-        self.tools = tools
+    pub fn tools(self, tools: impl IntoIterator<Item = Tool>) -> Self {
+        self.tools.replace(tools);
+        self
+    }
+
+    pub fn resources(mut self, resources: impl IntoIterator<Item = Resource>) -> Self {
+        self.resources = resources
+            .into_iter()
+            .map(|resource| (resource.uri.clone(), resource))
+            .collect();
+
+        self
+    }
+
+    pub fn resource_templates(
+        mut self,
+        templates: impl IntoIterator<Item = resource::Template>,
+    ) -> Self {
+        self.templates = templates.into_iter().collect();
+
+        self
+    }
+
+    pub fn prompts(mut self, prompts: impl IntoIterator<Item = Prompt>) -> Self {
+        self.prompts = prompts
             .into_iter()
-            .map(|tool| (tool.name.clone(), tool))
+            .map(|prompt| (prompt.name.clone(), prompt))
             .collect();
 
         self
     }
 
+    /// Sets the maximum amount of items returned in a single page
+    /// of any list.
+    ///
+    /// By default, lists are not paginated.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = Some(page_size.max(1));
+        self
+    }
+
+    pub fn handle(&self) -> Handle {
+        Handle::new(self.sessions.clone(), self.tools.clone())
+    }
+
     pub async fn run(self, mut transport: impl Transport) -> io::Result<()> {
         let server = Arc::new(self);
 
@@ -59,98 +120,255 @@ impl Server {
             let action = transport.accept().await?;
 
             match action {
-                Action::Subscribe(channel) => {
-                    let _ = channel.send(transport::Result::Reject);
+                Action::Subscribe(session, last_event, channel) => {
+                    let result = match server.sessions.get(&session) {
+                        Some(current)
+                            if session
+                                .version()
+                                .is_some_and(|version| version != current.version()) =>
+                        {
+                            transport::Result::Reject
+                        }
+                        Some(session) => transport::Result::Stream(session.listen(last_event)),
+                        None if session == transport::Session::None => transport::Result::Reject,
+                        None => transport::Result::NotFound,
+                    };
+
+                    let _ = channel.send(result);
                 }
-                Action::Handle(bytes, channel) => {
+                Action::Handle(session, bytes, channel) => {
                     let server = server.clone();
 
                     drop(task::spawn(async move {
-                        if let Err(error) = server.handle(bytes, channel).await {
+                        if let Err(error) = server.process(session, bytes, channel).await {
                             log::error!("{error}");
                         }
                     }));
                 }
+                Action::End(id, channel) => {
+                    let _ = channel.send(if server.sessions.end(&id) {
+                        transport::Result::Accept
+                    } else {
+                        transport::Result::NotFound
+                    });
+                }
                 Action::Quit => return Ok(()),
             }
         }
     }
 
-    async fn handle(&self, bytes: mcp::Bytes, channel: Channel) -> io::Result<()> {
-        match client::Message::<mcp::Value>::deserialize(&bytes) {
-            Ok(message) => match message {
-                client::Message::Request(request) => {
-                    self.serve(Connection::new(request.id, channel), request.payload)
-                        .await
-                }
-                client::Message::Notification(notification) => {
-                    self.deliver_notification(Receipt::new(channel), notification.payload)
-                        .await
-                }
-                client::Message::Response(response) => {
-                    self.deliver_response(Receipt::new(channel), response).await
-                }
-                client::Message::Error(error) => {
-                    self.deliver_error(Receipt::new(channel), error).await
-                }
-            },
+    async fn process(
+        &self,
+        session: transport::Session,
+        bytes: mcp::Bytes,
+        channel: Channel,
+    ) -> io::Result<()> {
+        let message = match client::Message::<mcp::Value>::deserialize(&bytes) {
+            Ok(message) => message,
             Err(error) => {
                 let bytes = mcp::Error::invalid_json(error.to_string()).serialize()?;
                 let _ = channel.send(transport::Result::Send(bytes));
 
-                Ok(())
+                return Ok(());
+            }
+        };
+
+        let Some(current) = self.sessions.get(&session) else {
+            return match (session, message) {
+                (
+                    transport::Session::None,
+                    client::Message::Request(mcp::Request {
+                        id,
+                        payload: client::Request::Initialize { params: initialize },
+                        ..
+                    }),
+                ) => {
+                    let Some((session_id, session)) = self.sessions.start() else {
+                        let _ = channel.send(transport::Result::Unavailable);
+
+                        return Ok(());
+                    };
+
+                    self.initialize(
+                        Connection::new(id, channel, session.clone()),
+                        &session,
+                        Some(session_id),
+                        initialize,
+                    )
+                    .await
+                }
+                (transport::Session::None, _) => {
+                    let _ = channel.send(transport::Result::Reject);
+
+                    Ok(())
+                }
+                (transport::Session::Single | transport::Session::Id(..), _) => {
+                    let _ = channel.send(transport::Result::NotFound);
+
+                    Ok(())
+                }
+            };
+        };
+
+        // Clients must keep using the protocol version they negotiated
+        if session
+            .version()
+            .is_some_and(|version| version != current.version())
+        {
+            let _ = channel.send(transport::Result::Reject);
+
+            return Ok(());
+        }
+
+        let session = current;
+
+        match message {
+            client::Message::Request(request) => {
+                self.serve(
+                    Connection::new(request.id, channel, session.clone()),
+                    &session,
+                    request.payload,
+                )
+                .await
+            }
+            client::Message::Notification(notification) => {
+                self.deliver_notification(Receipt::new(channel), &session, notification.payload)
+                    .await
+            }
+            client::Message::Response(response) => {
+                self.deliver_response(Receipt::new(channel), &session, response)
+                    .await
+            }
+            client::Message::Error(error) => {
+                self.deliver_error(Receipt::new(channel), &session, error)
+                    .await
             }
         }
     }
 
-    async fn serve(&self, connection: Connection, request: client::Request) -> io::Result<()> {
+    async fn serve(
+        &self,
+        connection: Connection,
+        session: &Session,
+        request: client::Request,
+    ) -> io::Result<()> {
         log::debug!("Serving {request:?}");
 
         match request {
-            client::Request::Initialize { .. } => self.initialize(connection).await,
+            client::Request::Initialize { params: initialize } => {
+                self.initialize(connection, session, None, initialize).await
+            }
             client::Request::Ping => self.ping(connection).await,
-            client::Request::ToolsList => self.list_tools(connection).await,
-            client::Request::ToolsCall { params: call } => self.call_tool(connection, call).await,
+            client::Request::ToolsList { params: page } => {
+                self.list_tools(connection, session, page).await
+            }
+            client::Request::ToolsCall { params: call } => {
+                self.call_tool(connection, session, call).await
+            }
+            client::Request::ResourcesList { params: page } => {
+                self.list_resources(connection, page).await
+            }
+            client::Request::ResourceTemplatesList { params: page } => {
+                self.list_resource_templates(connection, page).await
+            }
+            client::Request::ResourcesRead { params: read } => {
+                self.read_resource(connection, read).await
+            }
+            client::Request::ResourcesSubscribe { params: subscribe } => {
+                self.subscribe(connection, session, subscribe).await
+            }
+            client::Request::ResourcesUnsubscribe {
+                params: unsubscribe,
+            } => self.unsubscribe(connection, session, unsubscribe).await,
+            client::Request::PromptsList { params: page } => {
+                self.list_prompts(connection, page).await
+            }
+            client::Request::PromptsGet { params: get } => self.get_prompt(connection, get).await,
+            client::Request::LoggingSetLevel { params: set_level } => {
+                self.set_log_level(connection, session, set_level).await
+            }
+            client::Request::CompletionComplete { params: complete } => {
+                self.complete(connection, complete).await
+            }
         }
     }
 
-    async fn initialize(&self, connection: Connection) -> io::Result<()> {
+    async fn initialize(
+        &self,
+        connection: Connection,
+        session: &Session,
+        session_id: Option<String>,
+        initialize: client::request::Initialize,
+    ) -> io::Result<()> {
         use crate::mcp::server::capabilities::{self, Capabilities};
 
-        connection
-            .finish(response::Initialize {
-                protocol_version: mcp::VERSION.to_owned(),
-                capabilities: Capabilities {
-                    tools: (!self.tools.is_empty()).then_some(capabilities::Tools {
-                        list_changed: false, // TODO?
-                    }),
-                },
-                server_info: mcp::Server {
-                    name: self.name.clone(),
-                    version: self.version.clone(),
-                },
-            })
-            .await
+        let version = mcp::Version::negotiate(&initialize.protocol_version);
+
+        session.initialize(initialize.capabilities, version);
+
+        let response = response::Initialize {
+            protocol_version: version.to_string(),
+            capabilities: Capabilities {
+                tools: Some(capabilities::Tools { list_changed: true }),
+                resources: (!self.resources.is_empty() || !self.templates.is_empty()).then_some(
+                    capabilities::Resources {
+                        subscribe: true,
+                        list_changed: false,
+                    },
+                ),
+                prompts: (!self.prompts.is_empty()).then_some(capabilities::Prompts {
+                    list_changed: false,
+                }),
+                logging: Some(capabilities::Logging {}),
+                completions: (self.prompts.values().any(Prompt::is_completable)
+                    || self
+                        .templates
+                        .iter()
+                        .any(resource::Template::is_completable))
+                .then_some(capabilities::Completions {}),
+            },
+            server_info: mcp::Server {
+                name: self.name.clone(),
+                version: self.version.clone(),
+            },
+        };
+
+        match session_id {
+            Some(session_id) => connection.start(session_id, response).await,
+            None => connection.finish(response).await,
+        }
     }
 
     async fn ping(&self, connection: Connection) -> io::Result<()> {
         connection.finish(Response::Ping {}).await
     }
 
-    async fn list_tools(&self, connection: Connection) -> io::Result<()> {
+    async fn list_tools(
+        &self,
+        connection: Connection,
+        session: &Session,
+        page: client::request::Paginated,
+    ) -> io::Result<()> {
+        let has_output = session.version().supports_structured_output();
+
+        let (tools, next_cursor) =
+            match paginate(self.tools.list(), |tool| &tool.name, page, self.page_size) {
+                Ok(page) => page,
+                Err(error) => return connection.error(error).await,
+            };
+
         connection
             .finish(response::ToolsList {
-                tools: self
-                    .tools
-                    .values()
+                tools: tools
                     .map(|tool| server::Tool {
                         name: tool.name.clone(),
                         title: None,
                         description: tool.description.clone(),
                         input_schema: tool.input().clone(),
-                        output_schema: tool.output().cloned(),
+                        output_schema: tool.output().filter(|_| has_output).cloned(),
                     })
                     .collect(),
+                next_cursor,
             })
             .await
     }
@@ -158,10 +376,9 @@ impl Server {
     async fn call_tool(
         &self,
         mut connection: Connection,
+        session: &Session,
         call: client::request::ToolCall,
     ) -> io::Result<()> {
-        use futures::StreamExt;
-
         let Some(tool) = self.tools.get(&call.name) else {
             return connection
                 .error(mcp::ErrorKind::invalid_params(format!(
@@ -171,42 +388,445 @@ impl Server {
                 .await;
         };
 
-        let mut output = tool.call(call.arguments)?.boxed();
+        if let Err(error) = validation::validate(tool.input(), &call.arguments) {
+            let message = error.violations.iter().fold(
+                format!("Invalid arguments for tool {}:", &call.name),
+                |message, violation| format!("{message}\n- {violation}"),
+            );
+
+            return connection
+                .error(
+                    mcp::ErrorKind::invalid_params(message)
+                        .with_data(mcp::to_value(error.violations)?),
+                )
+                .await;
+        }
+
+        let (tool_call, cancellation) = Call::new(
+            connection.id(),
+            call.arguments,
+            call.meta.and_then(|meta| meta.progress_token),
+        );
+
+        // The call must be cancellable before its task is spawned
+        session.start_call(connection.id(), cancellation.clone());
+
+        let mut output = match tool.call(tool_call) {
+            Ok(output) => output,
+            Err(error) => {
+                session.finish_call(connection.id());
+
+                return connection
+                    .error(mcp::ErrorKind::invalid_params(format!(
+                        "Invalid arguments for tool {}: {error}",
+                        &call.name
+                    )))
+                    .await;
+            }
+        };
+        let mut requests = Vec::new();
+
+        let result = self
+            .run_tool(&mut connection, session, &mut output, &mut requests)
+            .await;
+
+        session.finish_call(connection.id());
+
+        if result.is_err() {
+            cancellation.abort();
+        }
+
+        for id in requests {
+            if session.abandon(id) {
+                let _ = session
+                    .notify(mcp::Cancelled {
+                        request_id: id,
+                        reason: Some("the tool call has finished".to_owned()),
+                    })
+                    .await;
+            }
+        }
+
+        match result? {
+            Some(mut outcome) => {
+                if let Some(schema) = tool.output()
+                    && let Err(error) = outcome.validate(schema)
+                {
+                    return connection
+                        .error(
+                            mcp::ErrorKind::internal_error(format!(
+                                "Invalid output of tool {}: {error}",
+                                tool.name
+                            ))
+                            .with_data(mcp::to_value(error.violations)?),
+                        )
+                        .await;
+                }
+
+                if !session.version().supports_structured_output() {
+                    outcome.content.structured = None;
+                }
+
+                connection.finish(outcome).await
+            }
+            None => connection.cancel(),
+        }
+    }
+
+    async fn run_tool(
+        &self,
+        connection: &mut Connection,
+        session: &Session,
+        output: &mut mpsc::Receiver<tool::Action>,
+        requests: &mut Vec<mcp::Id>,
+    ) -> io::Result<Option<server::tool::Response>> {
+        use futures::StreamExt;
 
         while let Some(action) = output.next().await {
             match action {
-                crate::tool::Action::Request(request) => connection.request(request).await?,
-                crate::tool::Action::Notify(notification) => {
+                tool::Action::Request(request, response) => {
+                    if !session.supports(&request) {
+                        let _ = response.send(Err(io::Error::new(
+                            io::ErrorKind::Unsupported,
+                            "the client does not support this request",
+                        )));
+
+                        continue;
+                    }
+
+                    if let server::Request::RootsList = request
+                        && let Some(roots) = session.roots()
+                    {
+                        let _ = response.send(Ok(roots));
+
+                        continue;
+                    }
+
+                    let id = session.request(&request, response);
+                    requests.push(id);
+
+                    connection.request(id, request).await?
+                }
+                tool::Action::Notify(notification) => {
+                    if let server::Notification::LoggingMessage { params: message } = &notification
+                        && message.level < session.log_level()
+                    {
+                        continue;
+                    }
+
                     connection.notify(notification).await?
                 }
-                crate::tool::Action::Finish(outcome) => return connection.finish(outcome?).await,
+                tool::Action::Finish(outcome) => return outcome.map(Some),
             }
         }
 
-        Ok(())
+        Ok(None)
+    }
+
+    async fn list_resources(
+        &self,
+        connection: Connection,
+        page: client::request::Paginated,
+    ) -> io::Result<()> {
+        let (resources, next_cursor) = match paginate(
+            self.resources.values(),
+            |resource| &resource.uri,
+            page,
+            self.page_size,
+        ) {
+            Ok(page) => page,
+            Err(error) => return connection.error(error).await,
+        };
+
+        connection
+            .finish(response::ResourcesList {
+                resources: resources
+                    .map(|resource| server::Resource {
+                        uri: resource.uri.clone(),
+                        name: resource.name.clone(),
+                        title: resource.title.clone(),
+                        description: resource.description.clone(),
+                        mime_type: resource.mime_type.clone(),
+                        size: None,
+                    })
+                    .collect(),
+                next_cursor,
+            })
+            .await
+    }
+
+    async fn list_resource_templates(
+        &self,
+        connection: Connection,
+        page: client::request::Paginated,
+    ) -> io::Result<()> {
+        let mut templates: Vec<_> = self.templates.iter().collect();
+        templates.sort_by(|a, b| a.uri_template.cmp(&b.uri_template));
+
+        let (templates, next_cursor) = match paginate(
+            templates,
+            |template| &template.uri_template,
+            page,
+            self.page_size,
+        ) {
+            Ok(page) => page,
+            Err(error) => return connection.error(error).await,
+        };
+
+        connection
+            .finish(response::ResourceTemplatesList {
+                resource_templates: templates
+                    .map(|template| server::resource::Template {
+                        uri_template: template.uri_template.clone(),
+                        name: template.name.clone(),
+                        title: template.title.clone(),
+                        description: template.description.clone(),
+                        mime_type: template.mime_type.clone(),
+                    })
+                    .collect(),
+                next_cursor,
+            })
+            .await
+    }
+
+    async fn read_resource(
+        &self,
+        connection: Connection,
+        read: client::request::ResourceRead,
+    ) -> io::Result<()> {
+        let contents = if let Some(resource) = self.resources.get(&read.uri) {
+            resource.read().await
+        } else if let Some((template, variables)) = self
+            .templates
+            .iter()
+            .find_map(|template| Some((template, template.matches(&read.uri)?)))
+        {
+            template.read(variables).await
+        } else {
+            return connection
+                .error(mcp::ErrorKind::resource_not_found(read.uri))
+                .await;
+        };
+
+        match contents {
+            Ok(contents) => {
+                connection
+                    .finish(response::ResourcesRead {
+                        contents: vec![contents],
+                    })
+                    .await
+            }
+            Err(error) => {
+                connection
+                    .error(mcp::ErrorKind::internal_error(error.to_string()))
+                    .await
+            }
+        }
+    }
+
+    async fn subscribe(
+        &self,
+        connection: Connection,
+        session: &Session,
+        subscribe: client::request::Subscribe,
+    ) -> io::Result<()> {
+        session.subscribe(subscribe.uri);
+
+        connection.finish(Response::Empty {}).await
+    }
+
+    async fn unsubscribe(
+        &self,
+        connection: Connection,
+        session: &Session,
+        unsubscribe: client::request::Unsubscribe,
+    ) -> io::Result<()> {
+        session.unsubscribe(&unsubscribe.uri);
+
+        connection.finish(Response::Empty {}).await
+    }
+
+    async fn list_prompts(
+        &self,
+        connection: Connection,
+        page: client::request::Paginated,
+    ) -> io::Result<()> {
+        let (prompts, next_cursor) = match paginate(
+            self.prompts.values(),
+            |prompt| &prompt.name,
+            page,
+            self.page_size,
+        ) {
+            Ok(page) => page,
+            Err(error) => return connection.error(error).await,
+        };
+
+        connection
+            .finish(response::PromptsList {
+                prompts: prompts
+                    .map(|prompt| server::Prompt {
+                        name: prompt.name.clone(),
+                        title: prompt.title.clone(),
+                        description: prompt.description.clone(),
+                        arguments: prompt.arguments().to_vec(),
+                    })
+                    .collect(),
+                next_cursor,
+            })
+            .await
+    }
+
+    async fn get_prompt(
+        &self,
+        connection: Connection,
+        get: client::request::PromptGet,
+    ) -> io::Result<()> {
+        let Some(prompt) = self.prompts.get(&get.name) else {
+            return connection
+                .error(mcp::ErrorKind::invalid_params(format!(
+                    "Unknown prompt: {}",
+                    &get.name
+                )))
+                .await;
+        };
+
+        match prompt.get(get.arguments).await {
+            Ok(messages) => {
+                connection
+                    .finish(response::PromptsGet {
+                        description: prompt.description.clone(),
+                        messages,
+                    })
+                    .await
+            }
+            Err(error) if error.kind() == io::ErrorKind::InvalidInput => {
+                connection
+                    .error(mcp::ErrorKind::invalid_params(error.to_string()))
+                    .await
+            }
+            Err(error) => {
+                connection
+                    .error(mcp::ErrorKind::internal_error(error.to_string()))
+                    .await
+            }
+        }
+    }
+
+    async fn complete(
+        &self,
+        connection: Connection,
+        complete: client::request::Complete,
+    ) -> io::Result<()> {
+        let argument = complete.argument;
+
+        let completion = match &complete.reference {
+            Reference::Prompt { name } => {
+                let Some(prompt) = self.prompts.get(name) else {
+                    return connection
+                        .error(mcp::ErrorKind::invalid_params(format!(
+                            "Unknown prompt: {name}"
+                        )))
+                        .await;
+                };
+
+                prompt.complete(&argument.name, argument.value).await
+            }
+            Reference::Resource { uri } => {
+                let Some(template) = self
+                    .templates
+                    .iter()
+                    .find(|template| &template.uri_template == uri)
+                else {
+                    return connection
+                        .error(mcp::ErrorKind::invalid_params(format!(
+                            "Unknown resource template: {uri}"
+                        )))
+                        .await;
+                };
+
+                template.complete(&argument.name, argument.value).await
+            }
+        };
+
+        match completion {
+            Ok(completion) => connection.finish(response::Complete { completion }).await,
+            Err(error) => {
+                connection
+                    .error(mcp::ErrorKind::internal_error(error.to_string()))
+                    .await
+            }
+        }
+    }
+
+    async fn set_log_level(
+        &self,
+        connection: Connection,
+        session: &Session,
+        set_level: client::request::SetLevel,
+    ) -> io::Result<()> {
+        session.set_log_level(set_level.level);
+
+        connection.finish(Response::Empty {}).await
     }
 
     async fn deliver_notification(
         &self,
         receipt: Receipt,
-        _notification: client::Notification,
+        session: &Session,
+        notification: client::Notification,
     ) -> io::Result<()> {
-        // TODO
-        receipt.reject();
+        match notification {
+            client::Notification::Initialized => {}
+            client::Notification::Cancelled { params: cancelled } => {
+                if let Some(reason) = &cancelled.reason {
+                    log::debug!("Request {:?} was cancelled: {reason}", cancelled.request_id);
+                }
+
+                session.cancel_call(cancelled.request_id);
+            }
+            client::Notification::RootsListChanged => {
+                session.invalidate_roots();
+            }
+        }
+
+        receipt.accept();
 
         Ok(())
     }
 
-    async fn deliver_response(&self, receipt: Receipt, _response: mcp::Response) -> io::Result<()> {
-        // TODO
-        receipt.reject();
+    async fn deliver_response(
+        &self,
+        receipt: Receipt,
+        session: &Session,
+        response: mcp::Response,
+    ) -> io::Result<()> {
+        if session.respond(response.id, Ok(response.result)) {
+            receipt.accept();
+        } else {
+            receipt.reject();
+        }
 
         Ok(())
     }
 
-    async fn deliver_error(&self, receipt: Receipt, _error: mcp::Error) -> io::Result<()> {
-        // TODO
-        receipt.reject();
+    async fn deliver_error(
+        &self,
+        receipt: Receipt,
+        session: &Session,
+        error: mcp::Error,
+    ) -> io::Result<()> {
+        let Some(id) = error.id() else {
+            log::warn!("{error}");
+            receipt.reject();
+
+            return Ok(());
+        };
+
+        if session.respond(id, Err(error.into())) {
+            receipt.accept();
+        } else {
+            receipt.reject();
+        }
 
         Ok(())
     }
@@ -270,3 +890,727 @@ pub async fn transport(mut args: env::Args) -> io::Result<impl Transport> {
 
     Ok(HttpOrStdio::Stdio(Stdio::current()))
 }
+
+/// Paginates items sorted by a unique key.
+///
+/// Cursors encode the key of the last item of a page, so pages stay
+/// consistent when items are added or removed between requests.
+fn paginate<T>(
+    items: impl IntoIterator<Item = T>,
+    key: impl Fn(&T) -> &str,
+    page: client::request::Paginated,
+    page_size: Option<usize>,
+) -> Result<(impl Iterator<Item = T>, Option<String>), mcp::ErrorKind> {
+    let last =
+        match page.cursor {
+            Some(cursor) => Some(decode_cursor(&cursor).ok_or_else(|| {
+                mcp::ErrorKind::invalid_params(format!("Invalid cursor: {cursor}"))
+            })?),
+            None => None,
+        };
+
+    let mut items = items
+        .into_iter()
+        .skip_while(|item| last.as_deref().is_some_and(|last| key(item) <= last));
+
+    let page: Vec<T> = match page_size {
+        Some(page_size) => items.by_ref().take(page_size).collect(),
+        None => items.by_ref().collect(),
+    };
+
+    let next_cursor = if items.next().is_some() {
+        page.last().map(|item| encode_cursor(key(item)))
+    } else {
+        None
+    };
+
+    Ok((page.into_iter(), next_cursor))
+}
+
+fn encode_cursor(key: &str) -> String {
+    key.bytes().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_cursor(cursor: &str) -> Option<String> {
+    if !cursor.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| {
+            let hex = cursor.get(i..i + 2)?;
+
+            if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+                return None;
+            }
+
+            u8::from_str_radix(hex, 16).ok()
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::{Value, json};
+
+    use futures::channel::oneshot;
+    use futures::{SinkExt, StreamExt, future};
+
+    /// A transport that lets tests act as the client of a [`Server`].
+    struct Test {
+        actions: mpsc::Receiver<Action>,
+    }
+
+    impl Transport for Test {
+        async fn accept(&mut self) -> io::Result<Action> {
+            Ok(self.actions.next().await.unwrap_or(Action::Quit))
+        }
+    }
+
+    /// A client talking to a running [`Server`].
+    struct Client {
+        actions: mpsc::Sender<Action>,
+        next_request: u64,
+    }
+
+    impl Client {
+        fn run(server: Server) -> Self {
+            let (actions, receiver) = mpsc::channel(1);
+
+            drop(task::spawn(server.run(Test { actions: receiver })));
+
+            Self {
+                actions,
+                next_request: 0,
+            }
+        }
+
+        /// Sends an action to the server without waiting for its result.
+        async fn act(
+            &mut self,
+            action: impl FnOnce(transport::Channel) -> Action,
+        ) -> oneshot::Receiver<transport::Result> {
+            let (sender, receiver) = oneshot::channel();
+
+            self.actions
+                .send(action(sender))
+                .await
+                .expect("server must be running");
+
+            receiver
+        }
+
+        async fn send(&mut self, message: Value) -> oneshot::Receiver<transport::Result> {
+            self.act(|channel| {
+                Action::Handle(
+                    transport::Session::Single,
+                    message.to_string().into(),
+                    channel,
+                )
+            })
+            .await
+        }
+
+        /// Sends a request to the single session of the server without
+        /// waiting for its response.
+        async fn start(
+            &mut self,
+            method: &str,
+            params: Value,
+        ) -> (u64, oneshot::Receiver<transport::Result>) {
+            self.next_request += 1;
+
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": self.next_request,
+                "method": method,
+                "params": params,
+            });
+
+            (self.next_request, self.send(request).await)
+        }
+
+        /// Sends a request to the single session of the server and
+        /// returns its response.
+        async fn request(&mut self, method: &str, params: Value) -> Value {
+            let (_id, result) = self.start(method, params).await;
+
+            let mut messages = messages(result).await;
+            assert_eq!(messages.len(), 1, "expected a single response");
+
+            messages.remove(0)
+        }
+
+        async fn notify(&mut self, method: &str, params: Value) -> transport::Result {
+            let notification = json!({
+                "jsonrpc": "2.0",
+                "method": method,
+                "params": params,
+            });
+
+            self.send(notification)
+                .await
+                .await
+                .expect("server must respond")
+        }
+    }
+
+    /// Returns all the messages sent as the result of an action.
+    async fn messages(result: oneshot::Receiver<transport::Result>) -> Vec<Value> {
+        let parse = |bytes: mcp::Bytes| -> Value {
+            serde_json::from_slice(&bytes).expect("message must be valid JSON")
+        };
+
+        match result.await.expect("server must respond") {
+            transport::Result::Send(bytes) => vec![parse(bytes)],
+            transport::Result::Stream(stream) => {
+                stream
+                    .filter_map(async |event| event.message.map(parse))
+                    .collect()
+                    .await
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn page(cursor: Option<&str>) -> client::request::Paginated {
+        client::request::Paginated {
+            cursor: cursor.map(str::to_owned),
+        }
+    }
+
+    fn list(
+        items: &[&str],
+        cursor: Option<&str>,
+        page_size: Option<usize>,
+    ) -> Option<(Vec<String>, Option<String>)> {
+        let (items, next_cursor) =
+            paginate(items.iter().copied(), |item| item, page(cursor), page_size).ok()?;
+
+        Some((items.map(str::to_owned).collect(), next_cursor))
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        for key in ["", "echo", "file:///a b/é", "🦀"] {
+            assert_eq!(decode_cursor(&encode_cursor(key)).as_deref(), Some(key));
+        }
+    }
+
+    #[test]
+    fn invalid_cursors() {
+        for cursor in ["a", "6", "zz", "+f", "ff", "é1"] {
+            assert_eq!(decode_cursor(cursor), None, "{cursor:?} must be invalid");
+        }
+
+        assert_eq!(list(&["a", "b"], Some("zz"), Some(1)), None);
+    }
+
+    #[test]
+    fn unpaginated_lists() {
+        assert_eq!(
+            list(&["a", "b", "c"], None, None),
+            Some((vec!["a".to_owned(), "b".to_owned(), "c".to_owned()], None))
+        );
+        assert_eq!(list(&[], None, Some(1)), Some((vec![], None)));
+    }
+
+    #[test]
+    fn page_boundaries() {
+        let items = ["a", "b", "c", "d"];
+
+        let (first, cursor) = list(&items, None, Some(2)).expect("cursor must be valid");
+        assert_eq!(first, ["a", "b"]);
+
+        let (second, cursor) =
+            list(&items, cursor.as_deref(), Some(2)).expect("cursor must be valid");
+        assert_eq!(second, ["c", "d"]);
+        assert_eq!(cursor, None);
+
+        let (all, cursor) = list(&items, None, Some(4)).expect("cursor must be valid");
+        assert_eq!(all, items);
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn page_size_of_one() {
+        let items = ["a", "b", "c"];
+        let mut cursor = None;
+        let mut pages = Vec::new();
+
+        loop {
+            let (page, next_cursor) =
+                list(&items, cursor.as_deref(), Some(1)).expect("cursor must be valid");
+
+            pages.push(page);
+            cursor = next_cursor;
+
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        assert_eq!(pages, [["a"], ["b"], ["c"]]);
+    }
+
+    #[test]
+    fn removed_keys_are_skipped() {
+        let (_, cursor) = list(&["a", "b", "c"], None, Some(2)).expect("cursor must be valid");
+
+        assert_eq!(
+            list(&["a", "c"], cursor.as_deref(), Some(2)),
+            Some((vec!["c".to_owned()], None))
+        );
+    }
+
+    #[tokio::test]
+    async fn paginated_resource_templates() {
+        let server = Server::new("test", "1.0")
+            .resource_templates([
+                resource::template("c://{id}", |_| async { "" }).name("c"),
+                resource::template("a://{id}", |_| async { "" }).name("a"),
+                resource::template("b://{id}", |_| async { "" }).name("b"),
+            ])
+            .page_size(2);
+
+        let mut client = Client::run(server);
+
+        let first = client.request("resources/templates/list", json!({})).await;
+        let templates = &first["result"]["resourceTemplates"];
+
+        assert_eq!(templates[0]["uriTemplate"], "a://{id}");
+        assert_eq!(templates[1]["uriTemplate"], "b://{id}");
+
+        let cursor = first["result"]["nextCursor"].clone();
+        let second = client
+            .request("resources/templates/list", json!({ "cursor": cursor }))
+            .await;
+        let templates = &second["result"]["resourceTemplates"];
+
+        assert_eq!(templates.as_array().map(Vec::len), Some(1));
+        assert_eq!(templates[0]["uriTemplate"], "c://{id}");
+        assert_eq!(second["result"].get("nextCursor"), None);
+
+        let invalid = client
+            .request("resources/templates/list", json!({ "cursor": "zz" }))
+            .await;
+
+        assert_eq!(invalid["error"]["code"], -32602);
+    }
+
+    fn resources() -> Server {
+        Server::new("test", "1.0")
+            .resources([
+                resource::resource("file:///readme", async || "Hello!")
+                    .name("readme")
+                    .mime_type("text/plain"),
+                resource::resource("file:///logo", async || vec![0xFF, 0x00])
+                    .name("logo")
+                    .mime_type("image/png"),
+                resource::resource("file:///broken", async || {
+                    Err::<String, _>(io::Error::other("disk is on fire"))
+                })
+                .name("broken"),
+            ])
+            .resource_templates([resource::template(
+                "file:///users/{name}",
+                async |variables: resource::Variables| {
+                    format!("Hello, {}!", variables.get("name").unwrap_or_default())
+                },
+            )
+            .name("user")])
+    }
+
+    #[tokio::test]
+    async fn resources_are_listed() {
+        let mut client = Client::run(resources());
+
+        let list = client.request("resources/list", json!({})).await;
+        let resources = &list["result"]["resources"];
+
+        assert_eq!(resources.as_array().map(Vec::len), Some(3));
+        assert_eq!(resources[0]["uri"], "file:///broken");
+        assert_eq!(resources[1]["name"], "logo");
+        assert_eq!(resources[1]["mimeType"], "image/png");
+        assert_eq!(resources[2]["uri"], "file:///readme");
+
+        let list = client.request("resources/templates/list", json!({})).await;
+
+        assert_eq!(
+            list["result"]["resourceTemplates"][0]["uriTemplate"],
+            "file:///users/{name}"
+        );
+    }
+
+    #[tokio::test]
+    async fn resources_are_read() {
+        let mut client = Client::run(resources());
+
+        let readme = client
+            .request("resources/read", json!({ "uri": "file:///readme" }))
+            .await;
+
+        assert_eq!(
+            readme["result"]["contents"],
+            json!([{ "uri": "file:///readme", "mimeType": "text/plain", "text": "Hello!" }])
+        );
+
+        let logo = client
+            .request("resources/read", json!({ "uri": "file:///logo" }))
+            .await;
+
+        assert_eq!(logo["result"]["contents"][0]["blob"], "/wA=");
+
+        let user = client
+            .request(
+                "resources/read",
+                json!({ "uri": "file:///users/J%C3%BCrgen" }),
+            )
+            .await;
+
+        assert_eq!(
+            user["result"]["contents"],
+            json!([{ "uri": "file:///users/J%C3%BCrgen", "text": "Hello, Jürgen!" }])
+        );
+    }
+
+    #[tokio::test]
+    async fn unreadable_resources() {
+        let mut client = Client::run(resources());
+
+        let unknown = client
+            .request("resources/read", json!({ "uri": "file:///users/a/b" }))
+            .await;
+
+        assert_eq!(unknown["error"]["code"], -32002);
+
+        let broken = client
+            .request("resources/read", json!({ "uri": "file:///broken" }))
+            .await;
+
+        assert_eq!(broken["error"]["code"], -32603);
+        assert_eq!(broken["error"]["message"], "disk is on fire");
+    }
+
+    #[tokio::test]
+    async fn prompts_are_served() {
+        let server = Server::new("test", "1.0").prompts([prompt::prompt(
+            async |name: String| format!("Greet {name}"),
+            prompt::string("name", "The name to greet"),
+        )
+        .name("greet")
+        .description("Greets someone")]);
+
+        let mut client = Client::run(server);
+
+        let list = client.request("prompts/list", json!({})).await;
+
+        assert_eq!(
+            list["result"]["prompts"],
+            json!([{
+                "name": "greet",
+                "description": "Greets someone",
+                "arguments": [{
+                    "name": "name",
+                    "description": "The name to greet",
+                    "required": true,
+                }],
+            }])
+        );
+
+        let get = client
+            .request(
+                "prompts/get",
+                json!({ "name": "greet", "arguments": { "name": "Ferris" } }),
+            )
+            .await;
+
+        assert_eq!(get["result"]["description"], "Greets someone");
+        assert_eq!(
+            get["result"]["messages"][0]["content"]["text"],
+            "Greet Ferris"
+        );
+
+        let missing = client
+            .request("prompts/get", json!({ "name": "greet" }))
+            .await;
+
+        assert_eq!(missing["error"]["code"], -32602);
+        assert_eq!(missing["error"]["message"], "missing argument: name");
+
+        let unknown = client
+            .request("prompts/get", json!({ "name": "wave" }))
+            .await;
+
+        assert_eq!(unknown["error"]["code"], -32602);
+    }
+
+    #[tokio::test]
+    async fn arguments_are_completed() {
+        let server = Server::new("test", "1.0")
+            .prompts([prompt::prompt(
+                async |name: String| name,
+                prompt::string("name", "The name"),
+            )
+            .name("greet")
+            .completion("name", async |value: String| {
+                ["Ferris", "Fiona", "Grace"]
+                    .into_iter()
+                    .filter(|name| name.starts_with(&value))
+                    .collect::<Vec<_>>()
+            })])
+            .resource_templates([resource::template("file:///{path}", async |_| "")
+                .name("file")
+                .completion("path", async |value: String| vec![format!("{value}.rs")])]);
+
+        let mut client = Client::run(server);
+
+        let initialize = client
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {},
+                    "clientInfo": { "name": "test", "version": "1.0" },
+                }),
+            )
+            .await;
+
+        assert_eq!(
+            initialize["result"]["capabilities"]["completions"],
+            json!({})
+        );
+
+        let prompt = client
+            .request(
+                "completion/complete",
+                json!({
+                    "ref": { "type": "ref/prompt", "name": "greet" },
+                    "argument": { "name": "name", "value": "F" },
+                }),
+            )
+            .await;
+
+        assert_eq!(
+            prompt["result"]["completion"],
+            json!({ "values": ["Ferris", "Fiona"] })
+        );
+
+        let resource = client
+            .request(
+                "completion/complete",
+                json!({
+                    "ref": { "type": "ref/resource", "uri": "file:///{path}" },
+                    "argument": { "name": "path", "value": "main" },
+                }),
+            )
+            .await;
+
+        assert_eq!(
+            resource["result"]["completion"]["values"],
+            json!(["main.rs"])
+        );
+
+        let unknown = client
+            .request(
+                "completion/complete",
+                json!({
+                    "ref": { "type": "ref/prompt", "name": "wave" },
+                    "argument": { "name": "name", "value": "F" },
+                }),
+            )
+            .await;
+
+        assert_eq!(unknown["error"]["code"], -32602);
+    }
+
+    #[tokio::test]
+    async fn progress_is_notified() {
+        let server = Server::new("test", "1.0").tools([tool::tool(
+            async |context: tool::Context, steps: u32| {
+                for step in 1..=steps {
+                    let _ = context
+                        .progress(f64::from(step), Some(f64::from(steps)), None)
+                        .await;
+                }
+
+                "Done".to_owned()
+            },
+            tool::u32("steps", "The amount of steps"),
+        )
+        .name("count")
+        .description("Counts steps")]);
+
+        let mut client = Client::run(server);
+
+        let (_id, result) = client
+            .start(
+                "tools/call",
+                json!({
+                    "name": "count",
+                    "arguments": { "steps": 2 },
+                    "_meta": { "progressToken": "counting" },
+                }),
+            )
+            .await;
+
+        let messages = messages(result).await;
+
+        assert_eq!(messages.len(), 3);
+
+        for (message, progress) in messages.iter().zip([1.0, 2.0]) {
+            assert_eq!(message["method"], "notifications/progress");
+            assert_eq!(
+                message["params"],
+                json!({ "progressToken": "counting", "progress": progress, "total": 2.0 })
+            );
+        }
+
+        assert_eq!(messages[2]["result"]["content"][0]["text"], "Done");
+
+        // Without a token, the response is sent right away
+        let response = client
+            .request(
+                "tools/call",
+                json!({ "name": "count", "arguments": { "steps": 2 } }),
+            )
+            .await;
+
+        assert_eq!(response["result"]["content"][0]["text"], "Done");
+    }
+
+    #[tokio::test]
+    async fn tool_calls_are_cancelled() {
+        /// Reports when the tool call is dropped.
+        struct Guard(mpsc::UnboundedSender<&'static str>);
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                let _ = self.0.unbounded_send("dropped");
+            }
+        }
+
+        let (events, mut receiver) = mpsc::unbounded();
+
+        let server = Server::new("test", "1.0").tools([tool::tool(
+            move |_query: String| {
+                let guard = Guard(events.clone());
+
+                async move {
+                    let _ = guard.0.unbounded_send("started");
+                    future::pending::<()>().await;
+
+                    "Found".to_owned()
+                }
+            },
+            tool::string("query", "The query"),
+        )
+        .name("search")
+        .description("Searches forever")]);
+
+        let mut client = Client::run(server);
+
+        let (id, result) = client
+            .start(
+                "tools/call",
+                json!({ "name": "search", "arguments": { "query": "everything" } }),
+            )
+            .await;
+
+        assert_eq!(receiver.next().await, Some("started"));
+
+        let cancel = client
+            .notify(
+                "notifications/cancelled",
+                json!({ "requestId": id, "reason": "took too long" }),
+            )
+            .await;
+
+        assert!(matches!(cancel, transport::Result::Accept));
+        assert_eq!(receiver.next().await, Some("dropped"));
+
+        // Cancelled calls are never answered
+        assert!(matches!(result.await, Ok(transport::Result::Accept)));
+    }
+
+    #[tokio::test]
+    async fn sessions_are_started_and_ended() {
+        fn request(method: &str) -> mcp::Bytes {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": {
+                    "protocolVersion": "2025-03-26",
+                    "capabilities": {},
+                    "clientInfo": { "name": "test", "version": "1.0" },
+                },
+            })
+            .to_string()
+            .into()
+        }
+
+        let mut client = Client::run(Server::new("test", "1.0"));
+
+        let mut handle = async |session: &transport::Session, method: &str| {
+            let session = session.clone();
+
+            client
+                .act(|channel| Action::Handle(session, request(method), channel))
+                .await
+                .await
+                .expect("server must respond")
+        };
+
+        // Only initialization can start a session
+        let none = transport::Session::None;
+        assert!(matches!(
+            handle(&none, "ping").await,
+            transport::Result::Reject
+        ));
+
+        let transport::Result::Start(id, response) = handle(&none, "initialize").await else {
+            panic!("session must start");
+        };
+
+        let response: Value =
+            serde_json::from_slice(&response).expect("response must be valid JSON");
+        assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+
+        let session = transport::Session::Id(id.clone(), Some(mcp::Version::V2025_03_26));
+        assert!(matches!(
+            handle(&session, "ping").await,
+            transport::Result::Send(_)
+        ));
+
+        // Clients must keep using the negotiated version
+        let other_version = transport::Session::Id(id.clone(), Some(mcp::Version::V2025_06_18));
+        assert!(matches!(
+            handle(&other_version, "ping").await,
+            transport::Result::Reject
+        ));
+
+        let unknown = transport::Session::Id("unknown".to_owned(), None);
+        assert!(matches!(
+            handle(&unknown, "ping").await,
+            transport::Result::NotFound
+        ));
+
+        let mut end = async |id: &str| {
+            let id = id.to_owned();
+
+            client
+                .act(|channel| Action::End(id, channel))
+                .await
+                .await
+                .expect("server must respond")
+        };
+
+        assert!(matches!(end(&id).await, transport::Result::Accept));
+        assert!(matches!(end(&id).await, transport::Result::NotFound));
+    }
+}
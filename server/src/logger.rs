@@ -0,0 +1,166 @@
+use crate::mcp::{Value, logging};
+use crate::session::Sessions;
+
+use std::sync::Arc;
+
+/// A [`log::Log`] implementation that forwards records to the client
+/// as log messages.
+///
+/// Only the records of the `techne` and `tool` targets are forwarded,
+/// like `log::info!(target: "tool", "...")`; other crates may log
+/// sensitive details that clients should not see. More targets can be
+/// forwarded with [`Logger::target`].
+///
+/// Every session only receives the records at or above the level
+/// requested by its client.
+#[derive(Debug, Clone)]
+pub struct Logger {
+    sessions: Arc<Sessions>,
+    targets: Vec<String>,
+}
+
+impl Logger {
+    pub(crate) fn new(sessions: Arc<Sessions>) -> Self {
+        Self {
+            sessions,
+            targets: TARGETS.map(str::to_owned).to_vec(),
+        }
+    }
+
+    /// Forwards the records of the given target and its submodules too.
+    pub fn target(mut self, target: impl AsRef<str>) -> Self {
+        self.targets.push(target.as_ref().to_owned());
+        self
+    }
+
+    fn is_forwarded(&self, target: &str) -> bool {
+        self.targets.iter().any(|forwarded| {
+            target
+                .strip_prefix(forwarded.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+        })
+    }
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        let level = level(metadata.level());
+
+        self.is_forwarded(metadata.target())
+            && self
+                .sessions
+                .all()
+                .iter()
+                .any(|session| level >= session.log_level())
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if !self.is_forwarded(record.target()) {
+            return;
+        }
+
+        let message = logging::Message {
+            level: level(record.level()),
+            logger: Some(record.target().to_owned()),
+            data: Value::String(record.args().to_string()),
+        };
+
+        for session in self.sessions.all() {
+            if message.level >= session.log_level() {
+                let _ = session.try_notify(message.clone());
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn level(level: log::Level) -> logging::Level {
+    match level {
+        log::Level::Error => logging::Level::Error,
+        log::Level::Warn => logging::Level::Warning,
+        log::Level::Info => logging::Level::Info,
+        log::Level::Debug | log::Level::Trace => logging::Level::Debug,
+    }
+}
+
+/// The targets forwarded by default.
+const TARGETS: [&str; 2] = ["techne", "tool"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport;
+
+    use futures::StreamExt;
+    use futures::channel::mpsc;
+    use log::Log;
+
+    fn log(logger: &Logger, target: &str, level: log::Level, message: &str) {
+        logger.log(
+            &log::Record::builder()
+                .target(target)
+                .level(level)
+                .args(format_args!("{message}"))
+                .build(),
+        );
+    }
+
+    /// Returns the parameters of the log messages sent to a listener.
+    async fn messages(listener: mpsc::Receiver<transport::Event>) -> Vec<Value> {
+        listener
+            .filter_map(async |event| event.message)
+            .map(|bytes| {
+                let mut notification: Value =
+                    serde_json::from_slice(&bytes).expect("notification must be valid JSON");
+
+                assert_eq!(notification["method"], "notifications/message");
+
+                notification["params"].take()
+            })
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn only_forwarded_targets_are_logged() {
+        let sessions = Arc::new(Sessions::default());
+        let (id, session) = sessions.start().expect("session must start");
+        let listener = session.listen(None);
+
+        let logger = Logger::new(sessions.clone()).target("weather");
+
+        log(&logger, "tool", log::Level::Info, "tool");
+        log(&logger, "tool::search", log::Level::Info, "nested");
+        log(&logger, "weather", log::Level::Warn, "custom");
+        log(&logger, "toolbox", log::Level::Info, "similar");
+        log(&logger, "hyper::proto", log::Level::Info, "hyper");
+        log(&logger, "techne_server", log::Level::Error, "internal");
+        log(&logger, "tool", log::Level::Debug, "too verbose");
+
+        assert!(!logger.enabled(&log::Metadata::builder().target("hyper").build()));
+        assert!(logger.enabled(&log::Metadata::builder().target("techne").build()));
+
+        let _ = sessions.end(&id);
+
+        let messages = messages(listener).await;
+        let data: Vec<_> = messages.iter().map(|message| &message["data"]).collect();
+
+        assert_eq!(data, ["tool", "nested", "custom"]);
+        assert_eq!(messages[1]["logger"], "tool::search");
+        assert_eq!(messages[2]["level"], "warning");
+    }
+
+    #[test]
+    fn single_session_is_only_logged_when_used() {
+        let sessions = Arc::new(Sessions::default());
+        let logger = Logger::new(sessions.clone());
+        let metadata = log::Metadata::builder().target("tool").build();
+
+        assert!(!logger.enabled(&metadata));
+
+        let _ = sessions.get(&transport::Session::Single);
+
+        assert!(logger.enabled(&metadata));
+    }
+}
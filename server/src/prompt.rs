@@ -0,0 +1,403 @@
+use crate::completion::{Completions, IntoCompletion};
+use crate::mcp::completion::Completion;
+use crate::mcp::server::prompt::{self, Message};
+
+use futures::FutureExt;
+use futures::future::BoxFuture;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+pub struct Prompt<Name = String> {
+    pub name: Name,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    arguments: Vec<prompt::Argument>,
+    completions: Completions,
+    get: Box<Get>,
+}
+
+impl<Name> Prompt<Name> {
+    pub fn name(self, name: impl AsRef<str>) -> Prompt {
+        Prompt {
+            name: name.as_ref().to_owned(),
+            title: self.title,
+            description: self.description,
+            arguments: self.arguments,
+            completions: self.completions,
+            get: self.get,
+        }
+    }
+
+    pub fn title(self, title: impl AsRef<str>) -> Self {
+        Self {
+            title: Some(title.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    pub fn description(self, description: impl AsRef<str>) -> Self {
+        Self {
+            description: Some(description.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    /// Attaches a completion provider to the given argument.
+    ///
+    /// The provider receives the partial value of the argument.
+    pub fn completion<T, F>(
+        mut self,
+        argument: impl AsRef<str>,
+        complete: impl Fn(String) -> F + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: IntoCompletion + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.completions.insert(argument, complete);
+        self
+    }
+}
+
+impl Prompt {
+    pub fn arguments(&self) -> &[prompt::Argument] {
+        &self.arguments
+    }
+
+    pub async fn get(&self, arguments: BTreeMap<String, String>) -> io::Result<Vec<Message>> {
+        (self.get)(arguments)?.await
+    }
+
+    pub fn is_completable(&self) -> bool {
+        !self.completions.is_empty()
+    }
+
+    pub async fn complete(&self, argument: &str, value: String) -> io::Result<Completion> {
+        self.completions.complete(argument, value).await
+    }
+}
+
+pub fn prompt<A, O, F>(
+    f: impl Fn(A) -> F + Send + Sync + 'static,
+    a: impl Argument<A> + Send + Sync + 'static,
+) -> Prompt<()>
+where
+    O: IntoMessages + 'static,
+    F: Future<Output = O> + Send + 'static,
+{
+    let arguments = vec![describe(&a)];
+
+    let get = move |mut arguments: Arguments| {
+        let a = parse(&a, &mut arguments)?;
+        reject_unknown(arguments)?;
+
+        Ok(f(a).map(IntoMessages::into_messages).boxed())
+    };
+
+    Prompt {
+        name: (),
+        title: None,
+        description: None,
+        arguments,
+        completions: Completions::default(),
+        get: Box::new(get),
+    }
+}
+
+pub fn prompt_2<A, B, O, F>(
+    f: impl Fn(A, B) -> F + Send + Sync + 'static,
+    a: impl Argument<A> + Send + Sync + 'static,
+    b: impl Argument<B> + Send + Sync + 'static,
+) -> Prompt<()>
+where
+    O: IntoMessages + 'static,
+    F: Future<Output = O> + Send + 'static,
+{
+    let arguments = vec![describe(&a), describe(&b)];
+
+    let get = move |mut arguments: Arguments| {
+        let a = parse(&a, &mut arguments)?;
+        let b = parse(&b, &mut arguments)?;
+        reject_unknown(arguments)?;
+
+        Ok(f(a, b).map(IntoMessages::into_messages).boxed())
+    };
+
+    Prompt {
+        name: (),
+        title: None,
+        description: None,
+        arguments,
+        completions: Completions::default(),
+        get: Box::new(get),
+    }
+}
+
+pub trait IntoMessages {
+    fn into_messages(self) -> io::Result<Vec<Message>>;
+}
+
+impl IntoMessages for String {
+    fn into_messages(self) -> io::Result<Vec<Message>> {
+        Ok(vec![Message::user(self)])
+    }
+}
+
+impl IntoMessages for Message {
+    fn into_messages(self) -> io::Result<Vec<Message>> {
+        Ok(vec![self])
+    }
+}
+
+impl IntoMessages for Vec<Message> {
+    fn into_messages(self) -> io::Result<Vec<Message>> {
+        Ok(self)
+    }
+}
+
+impl<T, E> IntoMessages for Result<T, E>
+where
+    T: IntoMessages,
+    E: std::error::Error,
+{
+    fn into_messages(self) -> io::Result<Vec<Message>> {
+        match self {
+            Ok(messages) => messages.into_messages(),
+            Err(error) => Err(io::Error::other(error.to_string())),
+        }
+    }
+}
+
+pub trait Argument<T> {
+    fn name(&self) -> &str;
+
+    fn description(&self) -> &str;
+
+    fn parse(&self, value: Option<String>) -> io::Result<T>;
+
+    fn is_required(&self) -> bool {
+        true
+    }
+}
+
+pub fn string(name: impl AsRef<str>, description: impl AsRef<str>) -> impl Argument<String> {
+    NamedArg::new(name, description)
+}
+
+pub fn u32(name: impl AsRef<str>, description: impl AsRef<str>) -> impl Argument<u32> {
+    NamedArg::new(name, description)
+}
+
+pub fn f32(name: impl AsRef<str>, description: impl AsRef<str>) -> impl Argument<f32> {
+    NamedArg::new(name, description)
+}
+
+pub fn bool(name: impl AsRef<str>, description: impl AsRef<str>) -> impl Argument<bool> {
+    NamedArg::new(name, description)
+}
+
+pub fn optional<T>(argument: impl Argument<T>) -> impl Argument<Option<T>> {
+    struct Optional<A, T> {
+        argument: A,
+        _output: PhantomData<T>,
+    }
+
+    impl<A, T> Argument<Option<T>> for Optional<A, T>
+    where
+        A: Argument<T>,
+    {
+        fn name(&self) -> &str {
+            self.argument.name()
+        }
+
+        fn description(&self) -> &str {
+            self.argument.description()
+        }
+
+        fn parse(&self, value: Option<String>) -> io::Result<Option<T>> {
+            if value.is_none() {
+                return Ok(None);
+            }
+
+            self.argument.parse(value).map(Some)
+        }
+
+        fn is_required(&self) -> bool {
+            false
+        }
+    }
+
+    Optional {
+        argument,
+        _output: PhantomData,
+    }
+}
+
+struct NamedArg {
+    name: String,
+    description: String,
+}
+
+impl NamedArg {
+    fn new(name: impl AsRef<str>, description: impl AsRef<str>) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            description: description.as_ref().to_owned(),
+        }
+    }
+}
+
+impl<T> Argument<T> for NamedArg
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parse(&self, value: Option<String>) -> io::Result<T> {
+        let Some(value) = value else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("missing argument: {}", self.name),
+            ));
+        };
+
+        value.parse().map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid argument {}: {error}", self.name),
+            )
+        })
+    }
+}
+
+type Arguments = BTreeMap<String, String>;
+
+type Get =
+    dyn Fn(Arguments) -> io::Result<BoxFuture<'static, io::Result<Vec<Message>>>> + Send + Sync;
+
+fn describe<T>(arg: &impl Argument<T>) -> prompt::Argument {
+    prompt::Argument {
+        name: arg.name().to_owned(),
+        title: None,
+        description: Some(arg.description().to_owned()),
+        required: arg.is_required(),
+    }
+}
+
+fn parse<T>(arg: &impl Argument<T>, arguments: &mut Arguments) -> io::Result<T> {
+    arg.parse(arguments.remove(arg.name()))
+}
+
+fn reject_unknown(arguments: Arguments) -> io::Result<()> {
+    if let Some(name) = arguments.into_keys().next() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unknown argument: {name}"),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::{Value, json};
+
+    fn review() -> Prompt {
+        prompt_2(
+            async |code: String, lines: Option<u32>| {
+                format!("Review {} lines of:\n{code}", lines.unwrap_or(1))
+            },
+            string("code", "The code to review"),
+            optional(u32("lines", "The amount of lines")),
+        )
+        .name("review")
+    }
+
+    fn arguments<'a>(arguments: impl IntoIterator<Item = (&'a str, &'a str)>) -> Arguments {
+        arguments
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value.to_owned()))
+            .collect()
+    }
+
+    async fn get(prompt: &Prompt, arguments: Arguments) -> io::Result<Value> {
+        let messages = prompt.get(arguments).await?;
+
+        Ok(serde_json::to_value(messages).expect("messages must serialize"))
+    }
+
+    #[test]
+    fn arguments_are_described() {
+        let review = review();
+        let arguments = review.arguments();
+
+        assert_eq!(arguments.len(), 2);
+        assert_eq!(arguments[0].name, "code");
+        assert!(arguments[0].required);
+        assert_eq!(arguments[1].name, "lines");
+        assert!(!arguments[1].required);
+    }
+
+    #[tokio::test]
+    async fn typed_arguments() {
+        let review = review();
+
+        let messages = get(
+            &review,
+            arguments([("code", "fn main() {}"), ("lines", "3")]),
+        )
+        .await
+        .expect("prompt must succeed");
+
+        assert_eq!(
+            messages,
+            json!([{
+                "role": "user",
+                "content": { "type": "text", "text": "Review 3 lines of:\nfn main() {}" },
+            }])
+        );
+
+        let messages = get(&review, arguments([("code", "x")]))
+            .await
+            .expect("optional arguments may be missing");
+
+        assert_eq!(messages[0]["content"]["text"], "Review 1 lines of:\nx");
+    }
+
+    #[tokio::test]
+    async fn invalid_arguments() {
+        let review = review();
+
+        for (arguments, message) in [
+            (arguments([]), "missing argument: code"),
+            (
+                arguments([("code", "x"), ("lines", "many")]),
+                "invalid argument lines: invalid digit found in string",
+            ),
+            (
+                arguments([("code", "x"), ("focus", "style")]),
+                "unknown argument: focus",
+            ),
+        ] {
+            let error = get(&review, arguments)
+                .await
+                .expect_err("arguments must be invalid");
+
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+            assert_eq!(error.to_string(), message);
+        }
+    }
+}
@@ -0,0 +1,475 @@
+use crate::completion::{Completions, IntoCompletion};
+use crate::mcp::Bytes;
+use crate::mcp::completion::Completion;
+use crate::mcp::server::content::Base64;
+use crate::mcp::server::resource;
+
+use futures::FutureExt;
+use futures::future::BoxFuture;
+
+use std::collections::BTreeMap;
+use std::io;
+
+pub struct Resource<Name = String> {
+    pub uri: String,
+    pub name: Name,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+    read: Box<dyn Fn() -> BoxFuture<'static, io::Result<Contents>> + Send + Sync>,
+}
+
+pub fn resource<T, F>(
+    uri: impl AsRef<str>,
+    read: impl Fn() -> F + Send + Sync + 'static,
+) -> Resource<()>
+where
+    T: IntoContents + 'static,
+    F: Future<Output = T> + Send + 'static,
+{
+    Resource {
+        uri: uri.as_ref().to_owned(),
+        name: (),
+        title: None,
+        description: None,
+        mime_type: None,
+        read: Box::new(move || read().map(IntoContents::into_contents).boxed()),
+    }
+}
+
+impl<Name> Resource<Name> {
+    pub fn name(self, name: impl AsRef<str>) -> Resource {
+        Resource {
+            uri: self.uri,
+            name: name.as_ref().to_owned(),
+            title: self.title,
+            description: self.description,
+            mime_type: self.mime_type,
+            read: self.read,
+        }
+    }
+
+    pub fn title(self, title: impl AsRef<str>) -> Self {
+        Self {
+            title: Some(title.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    pub fn description(self, description: impl AsRef<str>) -> Self {
+        Self {
+            description: Some(description.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    pub fn mime_type(self, mime_type: impl AsRef<str>) -> Self {
+        Self {
+            mime_type: Some(mime_type.as_ref().to_owned()),
+            ..self
+        }
+    }
+}
+
+impl Resource {
+    pub async fn read(&self) -> io::Result<resource::Contents> {
+        let contents = (self.read)().await?;
+
+        Ok(contents.into_resource(self.uri.clone(), self.mime_type.clone()))
+    }
+}
+
+pub struct Template<Name = String> {
+    pub uri_template: String,
+    pub name: Name,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+    segments: Vec<Segment>,
+    completions: Completions,
+    read: Box<dyn Fn(Variables) -> BoxFuture<'static, io::Result<Contents>> + Send + Sync>,
+}
+
+/// Creates a resource [`Template`] that matches any URI of the given
+/// form.
+///
+/// Variables are written as `{name}` and match a single path segment.
+/// `{+name}` may also match across `/` boundaries. Variables must be
+/// separated by some literal text, and their values are percent-decoded.
+pub fn template<T, F>(
+    uri_template: impl AsRef<str>,
+    read: impl Fn(Variables) -> F + Send + Sync + 'static,
+) -> Template<()>
+where
+    T: IntoContents + 'static,
+    F: Future<Output = T> + Send + 'static,
+{
+    let uri_template = uri_template.as_ref().to_owned();
+
+    Template {
+        segments: Segment::parse(&uri_template),
+        completions: Completions::default(),
+        uri_template,
+        name: (),
+        title: None,
+        description: None,
+        mime_type: None,
+        read: Box::new(move |variables| read(variables).map(IntoContents::into_contents).boxed()),
+    }
+}
+
+impl<Name> Template<Name> {
+    pub fn name(self, name: impl AsRef<str>) -> Template {
+        Template {
+            uri_template: self.uri_template,
+            name: name.as_ref().to_owned(),
+            title: self.title,
+            description: self.description,
+            mime_type: self.mime_type,
+            segments: self.segments,
+            completions: self.completions,
+            read: self.read,
+        }
+    }
+
+    pub fn title(self, title: impl AsRef<str>) -> Self {
+        Self {
+            title: Some(title.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    pub fn description(self, description: impl AsRef<str>) -> Self {
+        Self {
+            description: Some(description.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    pub fn mime_type(self, mime_type: impl AsRef<str>) -> Self {
+        Self {
+            mime_type: Some(mime_type.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    /// Attaches a completion provider to the given variable.
+    ///
+    /// The provider receives the partial value of the variable.
+    pub fn completion<T, F>(
+        mut self,
+        variable: impl AsRef<str>,
+        complete: impl Fn(String) -> F + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: IntoCompletion + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.completions.insert(variable, complete);
+        self
+    }
+}
+
+impl Template {
+    pub fn is_completable(&self) -> bool {
+        !self.completions.is_empty()
+    }
+
+    pub async fn complete(&self, variable: &str, value: String) -> io::Result<Completion> {
+        self.completions.complete(variable, value).await
+    }
+
+    pub fn matches(&self, uri: &str) -> Option<Variables> {
+        Some(Variables {
+            uri: uri.to_owned(),
+            values: Segment::matches(&self.segments, uri)?,
+        })
+    }
+
+    pub async fn read(&self, variables: Variables) -> io::Result<resource::Contents> {
+        let uri = variables.uri.clone();
+        let contents = (self.read)(variables).await?;
+
+        Ok(contents.into_resource(uri, self.mime_type.clone()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variables {
+    uri: String,
+    values: BTreeMap<String, String>,
+}
+
+impl Variables {
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Contents {
+    Text(String),
+    Blob(Bytes),
+}
+
+impl Contents {
+    fn into_resource(self, uri: String, mime_type: Option<String>) -> resource::Contents {
+        match self {
+            Contents::Text(text) => resource::Contents::Text(resource::Text {
+                uri,
+                mime_type,
+                text,
+            }),
+            Contents::Blob(bytes) => resource::Contents::Blob(resource::Blob {
+                uri,
+                mime_type,
+                blob: Base64::encode(bytes),
+            }),
+        }
+    }
+}
+
+pub trait IntoContents {
+    fn into_contents(self) -> io::Result<Contents>;
+}
+
+impl IntoContents for Contents {
+    fn into_contents(self) -> io::Result<Contents> {
+        Ok(self)
+    }
+}
+
+impl IntoContents for String {
+    fn into_contents(self) -> io::Result<Contents> {
+        Ok(Contents::Text(self))
+    }
+}
+
+impl IntoContents for &'static str {
+    fn into_contents(self) -> io::Result<Contents> {
+        Ok(Contents::Text(self.to_owned()))
+    }
+}
+
+impl IntoContents for Vec<u8> {
+    fn into_contents(self) -> io::Result<Contents> {
+        Ok(Contents::Blob(Bytes::from_owner(self)))
+    }
+}
+
+impl IntoContents for Bytes {
+    fn into_contents(self) -> io::Result<Contents> {
+        Ok(Contents::Blob(self))
+    }
+}
+
+impl<T, E> IntoContents for Result<T, E>
+where
+    T: IntoContents,
+    E: std::error::Error,
+{
+    fn into_contents(self) -> io::Result<Contents> {
+        match self {
+            Ok(contents) => contents.into_contents(),
+            Err(error) => Err(io::Error::other(error.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Variable { name: String, reserved: bool },
+}
+
+impl Segment {
+    fn parse(template: &str) -> Vec<Self> {
+        let mut segments = Vec::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_owned()));
+            }
+
+            let variable = &rest[start + 1..start + end];
+
+            segments.push(match variable.strip_prefix('+') {
+                Some(name) => Segment::Variable {
+                    name: name.to_owned(),
+                    reserved: true,
+                },
+                None => Segment::Variable {
+                    name: variable.to_owned(),
+                    reserved: false,
+                },
+            });
+
+            rest = &rest[start + end + 1..];
+        }
+
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_owned()));
+        }
+
+        segments
+    }
+
+    /// Matches the segments against a URI, returning the percent-decoded
+    /// value of every variable.
+    ///
+    /// Variables end at the first occurrence of the literal that follows
+    /// them, or at its last occurrence for `{+name}` variables.
+    fn matches(segments: &[Self], uri: &str) -> Option<BTreeMap<String, String>> {
+        let mut values = BTreeMap::new();
+        let mut segments = segments.iter().peekable();
+        let mut rest = uri;
+
+        while let Some(segment) = segments.next() {
+            match segment {
+                Segment::Literal(literal) => {
+                    rest = rest.strip_prefix(literal.as_str())?;
+                }
+                Segment::Variable { name, reserved } => {
+                    let end = match segments.peek() {
+                        None => rest.len(),
+                        Some(Segment::Literal(literal)) if *reserved => {
+                            rest.rfind(literal.as_str())?
+                        }
+                        Some(Segment::Literal(literal)) => rest.find(literal.as_str())?,
+                        // Adjacent variables cannot be told apart
+                        Some(Segment::Variable { .. }) => return None,
+                    };
+
+                    let value = &rest[..end];
+
+                    if value.is_empty() || !reserved && value.contains('/') {
+                        return None;
+                    }
+
+                    let _ = values.insert(name.clone(), percent_decode(value)?);
+
+                    rest = &rest[end..];
+                }
+            }
+        }
+
+        rest.is_empty().then_some(values)
+    }
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = value.get(i + 1..i + 3)?;
+
+            if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+                return None;
+            }
+
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::template;
+
+    fn matches(uri_template: &str, uri: &str) -> Option<Vec<(String, String)>> {
+        let template = template(uri_template, |_| async { "" }).name("test");
+
+        template
+            .matches(uri)
+            .map(|variables| variables.values.into_iter().collect())
+    }
+
+    fn values(values: &[(&str, &str)]) -> Option<Vec<(String, String)>> {
+        Some(
+            values
+                .iter()
+                .map(|(name, value)| ((*name).to_owned(), (*value).to_owned()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn literal_templates() {
+        assert_eq!(matches("file:///readme", "file:///readme"), values(&[]));
+        assert_eq!(matches("file:///readme", "file:///readme.md"), None);
+    }
+
+    #[test]
+    fn simple_variables() {
+        assert_eq!(
+            matches("users://{id}/posts/{post}", "users://42/posts/7"),
+            values(&[("id", "42"), ("post", "7")])
+        );
+        assert_eq!(
+            matches("file:///{name}.txt", "file:///notes.txt"),
+            values(&[("name", "notes")])
+        );
+    }
+
+    #[test]
+    fn simple_variables_match_a_single_segment() {
+        assert_eq!(matches("file:///{name}", "file:///a/b"), None);
+        assert_eq!(matches("file:///{name}", "file:///"), None);
+        assert_eq!(matches("users://{id}/posts", "users://42/comments"), None);
+    }
+
+    #[test]
+    fn reserved_variables() {
+        assert_eq!(
+            matches("file:///{+path}", "file:///a/b/c.txt"),
+            values(&[("path", "a/b/c.txt")])
+        );
+        assert_eq!(
+            matches("file:///{+path}/{name}", "file:///a/b/c.txt"),
+            values(&[("name", "c.txt"), ("path", "a/b")])
+        );
+    }
+
+    #[test]
+    fn encoded_values() {
+        assert_eq!(
+            matches("search://{query}", "search://hello%20world%2F%C3%A9"),
+            values(&[("query", "hello world/é")])
+        );
+        assert_eq!(matches("search://{query}", "search://100%"), None);
+        assert_eq!(matches("search://{query}", "search://%zz"), None);
+        assert_eq!(matches("search://{query}", "search://%+1"), None);
+        assert_eq!(matches("search://{query}", "search://%FF"), None);
+    }
+
+    #[test]
+    fn adjacent_variables() {
+        assert_eq!(matches("x://{a}{b}", "x://ab"), None);
+    }
+
+    #[test]
+    fn many_variables_do_not_backtrack() {
+        let template = "x://".to_owned() + &"{a}-".repeat(32);
+        let uri = "x://".to_owned() + &"a-".repeat(31) + "a";
+
+        assert_eq!(matches(&template, &uri), None);
+    }
+}
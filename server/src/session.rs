@@ -0,0 +1,704 @@
+use crate::mcp;
+use crate::mcp::server::{Notification, Request};
+use crate::mcp::{Bytes, Id, Value, Version, client, logging};
+use crate::transport;
+
+use futures::SinkExt;
+use futures::channel::{mpsc, oneshot};
+use futures::future::AbortHandle;
+use uuid::Uuid;
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// The sessions of a server.
+#[derive(Debug, Default)]
+pub(crate) struct Sessions {
+    single: Arc<Session>,
+    /// Whether the transport serves a single client through the
+    /// `single` session.
+    is_single: AtomicBool,
+    sessions: Mutex<BTreeMap<String, Arc<Session>>>,
+}
+
+impl Sessions {
+    /// Returns the session of a transport action, if it exists.
+    pub fn get(&self, session: &transport::Session) -> Option<Arc<Session>> {
+        match session {
+            transport::Session::Single => {
+                self.is_single.store(true, Ordering::Relaxed);
+
+                Some(self.single.clone())
+            }
+            transport::Session::None => None,
+            transport::Session::Id(id, _) => {
+                let mut sessions = self.sessions();
+                let session = sessions.get(id)?.clone();
+
+                if session.is_expired() {
+                    let _ = sessions.remove(id);
+                    session.end();
+
+                    return None;
+                }
+
+                session.touch();
+
+                Some(session)
+            }
+        }
+    }
+
+    /// Starts a new session and returns its id.
+    ///
+    /// Returns `None` if there are too many sessions and all of them
+    /// are active.
+    pub fn start(&self) -> Option<(String, Arc<Session>)> {
+        // Session ids must be unguessable
+        let id = Uuid::new_v4().simple().to_string();
+
+        let session = Arc::new(Session::default());
+        session.touch();
+
+        let mut sessions = self.sessions();
+
+        sessions.retain(|_id, session| {
+            let is_expired = session.is_expired();
+
+            if is_expired {
+                session.end();
+            }
+
+            !is_expired
+        });
+
+        if sessions.len() >= MAX_SESSIONS {
+            let oldest = sessions
+                .iter()
+                .filter(|(_id, session)| session.is_idle())
+                .min_by_key(|(_id, session)| session.last_active())
+                .map(|(id, _session)| id.clone())?;
+
+            if let Some(oldest) = sessions.remove(&oldest) {
+                oldest.end();
+            }
+        }
+
+        let _ = sessions.insert(id.clone(), session.clone());
+
+        Some((id, session))
+    }
+
+    /// Ends the session with the given id.
+    ///
+    /// Returns `false` if there was no such session.
+    pub fn end(&self, id: &str) -> bool {
+        let Some(session) = self.sessions().remove(id) else {
+            return false;
+        };
+
+        session.end();
+
+        true
+    }
+
+    /// Returns all the sessions with a client.
+    pub fn all(&self) -> Vec<Arc<Session>> {
+        let mut all = Vec::new();
+
+        if self.is_single.load(Ordering::Relaxed) {
+            all.push(self.single.clone());
+        }
+
+        all.extend(self.sessions().values().cloned());
+
+        all
+    }
+
+    /// Sends a notification to every session, without waiting for any
+    /// of them to catch up.
+    pub fn notify(&self, notification: impl Into<Notification>) -> io::Result<()> {
+        let notification = notification.into();
+
+        for session in self.all() {
+            session.try_notify(notification.clone())?;
+        }
+
+        Ok(())
+    }
+
+    fn sessions(&self) -> MutexGuard<'_, BTreeMap<String, Arc<Session>>> {
+        self.sessions.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Session {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    capabilities: client::Capabilities,
+    version: Version,
+    subscriptions: BTreeSet<String>,
+    streams: BTreeMap<u64, mpsc::Sender<transport::Event>>,
+    next_stream: u64,
+    history: VecDeque<(u64, transport::Event)>,
+    next_event: u64,
+    next_request: Id,
+    pending: BTreeMap<Id, Pending>,
+    roots: Option<Value>,
+    calls: BTreeMap<Id, AbortHandle>,
+    log_level: logging::Level,
+    last_active: Option<Instant>,
+}
+
+impl State {
+    fn record(&mut self, stream: u64, message: Option<Bytes>) -> transport::Event {
+        let event = transport::Event {
+            id: self.next_event,
+            message,
+        };
+
+        self.next_event += 1;
+
+        if self.history.len() == HISTORY_LIMIT {
+            let _ = self.history.pop_front();
+        }
+
+        self.history.push_back((stream, event.clone()));
+
+        event
+    }
+}
+
+#[derive(Debug)]
+struct Pending {
+    response: oneshot::Sender<io::Result<Value>>,
+    is_roots: bool,
+}
+
+impl Session {
+    pub fn initialize(&self, capabilities: client::Capabilities, version: Version) {
+        let mut state = self.state();
+
+        state.capabilities = capabilities;
+        state.version = version;
+    }
+
+    /// Returns the protocol version negotiated with the client.
+    pub fn version(&self) -> Version {
+        self.state().version
+    }
+
+    /// Marks the session as active.
+    fn touch(&self) {
+        self.state().last_active = Some(Instant::now());
+    }
+
+    fn last_active(&self) -> Option<Instant> {
+        self.state().last_active
+    }
+
+    /// Returns `true` if the session is not running tool calls and its
+    /// client is not listening to the outbound stream.
+    fn is_idle(&self) -> bool {
+        !self.is_listening() && self.state().calls.is_empty()
+    }
+
+    /// Returns `true` if the session has been idle for too long.
+    fn is_expired(&self) -> bool {
+        self.is_idle()
+            && self
+                .last_active()
+                .is_some_and(|last_active| last_active.elapsed() > IDLE_TIMEOUT)
+    }
+
+    /// Stops all the activity of the session.
+    pub fn end(&self) {
+        let mut state = self.state();
+
+        for (_id, cancellation) in std::mem::take(&mut state.calls) {
+            cancellation.abort();
+        }
+
+        for (_id, pending) in std::mem::take(&mut state.pending) {
+            let _ = pending.response.send(Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "the session has ended",
+            )));
+        }
+
+        state.streams.clear();
+        state.history.clear();
+    }
+
+    pub fn supports(&self, request: &Request) -> bool {
+        let state = self.state();
+
+        match request {
+            Request::Ping => true,
+            Request::SamplingCreateMessage { .. } => state.capabilities.sampling.is_some(),
+            Request::ElicitationCreate { .. } => {
+                state.capabilities.elicitation.is_some() && state.version.supports_elicitation()
+            }
+            Request::RootsList => state.capabilities.roots.is_some(),
+        }
+    }
+
+    pub fn log_level(&self) -> logging::Level {
+        self.state().log_level
+    }
+
+    pub fn set_log_level(&self, level: logging::Level) {
+        self.state().log_level = level;
+    }
+
+    /// Opens the outbound stream of the session.
+    ///
+    /// If the given event id is still in the history of the session, the
+    /// stream of that event is resumed instead; replaying all of its later
+    /// events first.
+    ///
+    /// Every message is sent on a single stream, so resuming a stream
+    /// closes its previous connection.
+    pub fn listen(&self, last_event: Option<u64>) -> mpsc::Receiver<transport::Event> {
+        let mut state = self.state();
+
+        let resumed = last_event.and_then(|last_event| {
+            state
+                .history
+                .iter()
+                .find(|(_stream, event)| event.id == last_event)
+                .map(|(stream, _event)| (*stream, last_event))
+        });
+
+        let (stream, mut replay) = match resumed {
+            Some((stream, last_event)) => (
+                stream,
+                state
+                    .history
+                    .iter()
+                    .filter(|(candidate, event)| {
+                        *candidate == stream && event.id > last_event && event.message.is_some()
+                    })
+                    .map(|(_stream, event)| event.clone())
+                    .collect(),
+            ),
+            None => (OUTBOUND, Vec::new()),
+        };
+
+        // Every stream starts with an event, so the response begins right
+        // away and the client can resume it before any further messages
+        replay.push(state.record(stream, None));
+
+        let (mut sender, receiver) = mpsc::channel(STREAM_CAPACITY + replay.len());
+
+        for event in replay {
+            let _ = sender.try_send(event);
+        }
+
+        // Finished streams end right after the replay
+        if stream == OUTBOUND || state.streams.contains_key(&stream) {
+            let _ = state.streams.insert(stream, sender);
+        }
+
+        receiver
+    }
+
+    /// Opens a new stream of messages, like the response to a request.
+    pub fn open(self: &Arc<Self>) -> (Stream, mpsc::Receiver<transport::Event>) {
+        let mut state = self.state();
+        state.next_stream += 1;
+
+        let id = state.next_stream;
+        let (mut sender, receiver) = mpsc::channel(STREAM_CAPACITY);
+        let _ = sender.try_send(state.record(id, None));
+        let _ = state.streams.insert(id, sender);
+
+        (
+            Stream {
+                id,
+                session: self.clone(),
+            },
+            receiver,
+        )
+    }
+
+    pub fn is_listening(&self) -> bool {
+        self.state()
+            .streams
+            .get(&OUTBOUND)
+            .is_some_and(|outbound| !outbound.is_closed())
+    }
+
+    pub fn subscribe(&self, uri: String) {
+        let _ = self.state().subscriptions.insert(uri);
+    }
+
+    pub fn unsubscribe(&self, uri: &str) {
+        let _ = self.state().subscriptions.remove(uri);
+    }
+
+    pub fn is_subscribed(&self, uri: &str) -> bool {
+        self.state().subscriptions.contains(uri)
+    }
+
+    pub fn request(&self, request: &Request, response: oneshot::Sender<io::Result<Value>>) -> Id {
+        let mut state = self.state();
+        let id = state.next_request.increment();
+
+        let _ = state.pending.insert(
+            id,
+            Pending {
+                response,
+                is_roots: matches!(request, Request::RootsList),
+            },
+        );
+
+        id
+    }
+
+    pub fn respond(&self, id: Id, result: io::Result<Value>) -> bool {
+        let mut state = self.state();
+
+        let Some(pending) = state.pending.remove(&id) else {
+            return false;
+        };
+
+        if pending.is_roots
+            && let Ok(roots) = &result
+        {
+            state.roots = Some(roots.clone());
+        }
+
+        let _ = pending.response.send(result);
+
+        true
+    }
+
+    pub fn abandon(&self, id: Id) -> bool {
+        self.state().pending.remove(&id).is_some()
+    }
+
+    pub fn start_call(&self, id: Id, cancellation: AbortHandle) {
+        let _ = self.state().calls.insert(id, cancellation);
+    }
+
+    pub fn finish_call(&self, id: Id) {
+        let _ = self.state().calls.remove(&id);
+    }
+
+    pub fn cancel_call(&self, id: Id) {
+        if let Some(cancellation) = self.state().calls.remove(&id) {
+            cancellation.abort();
+        }
+    }
+
+    pub fn roots(&self) -> Option<Value> {
+        self.state().roots.clone()
+    }
+
+    pub fn invalidate_roots(&self) {
+        self.state().roots = None;
+    }
+
+    /// Sends a request to the client through the outbound channel and
+    /// waits for its response.
+    pub async fn send(&self, request: Request) -> io::Result<Value> {
+        let (sender, receiver) = oneshot::channel();
+        let id = self.request(&request, sender);
+
+        let bytes = mcp::Request::new(id, request).serialize()?;
+
+        if !self.push(bytes).await {
+            let _ = self.abandon(id);
+
+            return Err(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "the client is not listening to the server",
+            ));
+        }
+
+        receiver.await.unwrap_or_else(|_| {
+            Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "the request was abandoned",
+            ))
+        })
+    }
+
+    pub async fn notify(&self, notification: impl Into<Notification>) -> io::Result<()> {
+        let bytes = mcp::Notification::new(notification.into()).serialize()?;
+        let _ = self.push(bytes).await;
+
+        Ok(())
+    }
+
+    pub fn try_notify(&self, notification: impl Into<Notification>) -> io::Result<()> {
+        let bytes = mcp::Notification::new(notification.into()).serialize()?;
+
+        let mut state = self.state();
+        let event = state.record(OUTBOUND, Some(bytes));
+
+        if let Some(outbound) = state.streams.get_mut(&OUTBOUND) {
+            let _ = outbound.try_send(event);
+        }
+
+        Ok(())
+    }
+
+    /// Pushes a message to the outbound stream.
+    ///
+    /// Returns `false` if the stream is not connected.
+    async fn push(&self, bytes: Bytes) -> bool {
+        self.emit(OUTBOUND, bytes).await
+    }
+
+    /// Records a message in the history of the session and sends it
+    /// through the given stream.
+    ///
+    /// Returns `false` if the stream is not connected.
+    async fn emit(&self, stream: u64, message: Bytes) -> bool {
+        let (sender, event) = {
+            let mut state = self.state();
+            let event = state.record(stream, Some(message));
+
+            (state.streams.get(&stream).cloned(), event)
+        };
+
+        let Some(mut sender) = sender else {
+            return false;
+        };
+
+        sender.send(event).await.is_ok()
+    }
+
+    fn close(&self, stream: u64) {
+        let _ = self.state().streams.remove(&stream);
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A stream of messages of a [`Session`], closed when dropped.
+#[derive(Debug)]
+pub(crate) struct Stream {
+    id: u64,
+    session: Arc<Session>,
+}
+
+impl Stream {
+    pub async fn send(&self, message: Bytes) {
+        let _ = self.session.emit(self.id, message).await;
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        self.session.close(self.id);
+    }
+}
+
+/// The id of the stream that is not tied to any request.
+const OUTBOUND: u64 = 0;
+
+const STREAM_CAPACITY: usize = 10;
+
+/// The time after which an idle session expires.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// The maximum amount of sessions; the least recently active idle session
+/// is ended when a new one would exceed it.
+const MAX_SESSIONS: usize = 1024;
+
+/// The maximum amount of events kept to resume streams.
+const HISTORY_LIMIT: usize = 100;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::StreamExt;
+
+    fn fill(sessions: &Sessions) -> Vec<(String, Arc<Session>)> {
+        (0..MAX_SESSIONS)
+            .map(|_| sessions.start().expect("session must start"))
+            .collect()
+    }
+
+    #[test]
+    fn idle_sessions_are_evicted() {
+        let sessions = Sessions::default();
+        let started = fill(&sessions);
+
+        let _listeners: Vec<_> = started
+            .iter()
+            .skip(1)
+            .map(|(_id, session)| session.listen(None))
+            .collect();
+
+        let (id, _session) = sessions.start().expect("idle session must be evicted");
+
+        assert_eq!(sessions.sessions().len(), MAX_SESSIONS);
+        assert!(sessions.sessions().contains_key(&id));
+        assert!(!sessions.sessions().contains_key(&started[0].0));
+    }
+
+    #[test]
+    fn active_sessions_are_kept() {
+        let sessions = Sessions::default();
+        let started = fill(&sessions);
+
+        let mut listeners: Vec<_> = started
+            .iter()
+            .map(|(_id, session)| session.listen(None))
+            .collect();
+
+        assert!(sessions.start().is_none());
+
+        let (call, _registration) = AbortHandle::new_pair();
+        started[0].1.start_call(Id::default(), call);
+        drop(listeners.remove(0));
+
+        assert!(sessions.start().is_none());
+
+        started[0].1.finish_call(Id::default());
+
+        assert!(sessions.start().is_some());
+        assert!(!sessions.sessions().contains_key(&started[0].0));
+    }
+
+    #[tokio::test]
+    async fn ended_sessions_are_removed() {
+        let sessions = Sessions::default();
+        let (id, session) = sessions.start().expect("session must start");
+        let listener = session.listen(None);
+
+        assert!(
+            sessions
+                .get(&transport::Session::Id(id.clone(), None))
+                .is_some()
+        );
+        assert!(sessions.end(&id));
+        assert!(!sessions.end(&id));
+        assert!(sessions.get(&transport::Session::Id(id, None)).is_none());
+
+        // Only the opening event of the stream is left
+        assert_eq!(listener.collect::<Vec<_>>().await.len(), 1);
+    }
+
+    /// Returns the ids and messages of all the events of a stream.
+    async fn events(stream: mpsc::Receiver<transport::Event>) -> Vec<(u64, Option<Bytes>)> {
+        stream
+            .map(|event| (event.id, event.message))
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn finished_streams_are_replayed() {
+        let session = Arc::new(Session::default());
+        let (stream, receiver) = session.open();
+
+        stream.send(Bytes::from_static(b"progress")).await;
+        stream.send(Bytes::from_static(b"response")).await;
+        drop(stream);
+
+        let original = events(receiver).await;
+        assert_eq!(original.len(), 3);
+
+        // Messages of other streams are never replayed
+        session
+            .try_notify(Notification::ToolsListChanged)
+            .expect("notification must serialize");
+
+        let (last_event, _message) = &original[1];
+        let replay = events(session.listen(Some(*last_event))).await;
+
+        assert_eq!(replay.len(), 2);
+        assert_eq!(replay[0], original[2]);
+        assert_eq!(replay[1].1, None);
+        assert!(replay[1].0 > original[2].0);
+    }
+
+    #[tokio::test]
+    async fn open_streams_are_resumed() {
+        let session = Arc::new(Session::default());
+        let (stream, mut receiver) = session.open();
+
+        let priming = receiver.next().await.expect("stream must start").id;
+        drop(receiver);
+
+        stream.send(Bytes::from_static(b"missed")).await;
+
+        let resumed = session.listen(Some(priming));
+        stream.send(Bytes::from_static(b"live")).await;
+        drop(stream);
+
+        let messages: Vec<_> = events(resumed)
+            .await
+            .into_iter()
+            .filter_map(|(_id, message)| message)
+            .collect();
+
+        assert_eq!(
+            messages,
+            [Bytes::from_static(b"missed"), Bytes::from_static(b"live")]
+        );
+    }
+
+    #[tokio::test]
+    async fn outbound_stream_is_resumed() {
+        let session = Session::default();
+        let listener = session.listen(None);
+
+        session
+            .try_notify(Notification::ToolsListChanged)
+            .expect("notification must serialize");
+
+        drop(listener);
+
+        session
+            .try_notify(Notification::ToolsListChanged)
+            .expect("notification must serialize");
+
+        // The first notification was received before disconnecting
+        let first = session
+            .state()
+            .history
+            .iter()
+            .find(|(_stream, event)| event.message.is_some())
+            .map(|(_stream, event)| event.id)
+            .expect("notification must be recorded");
+
+        let mut resumed = session.listen(Some(first));
+        let missed = resumed.next().await.expect("missed event must be replayed");
+
+        assert!(missed.id > first);
+        assert!(missed.message.is_some());
+        assert_eq!(resumed.next().await.map(|event| event.message), Some(None));
+        assert!(session.is_listening());
+    }
+
+    #[tokio::test]
+    async fn unknown_events_start_a_new_stream() {
+        let session = Session::default();
+
+        session
+            .try_notify(Notification::ToolsListChanged)
+            .expect("notification must serialize");
+
+        let mut listener = session.listen(Some(42));
+        let event = listener.next().await.expect("stream must start");
+
+        assert_eq!(event.message, None);
+        assert!(session.is_listening());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::mcp::Bytes;
-use crate::transport::{Action, Result, Transport};
+use crate::transport::{Action, Result, Session, Transport};
 
 use futures::channel::mpsc;
 use futures::channel::oneshot;
@@ -9,7 +9,8 @@ use tokio::task;
 
 pub struct Stdio {
     input: BufReader<Box<dyn AsyncRead + Send + Unpin>>,
-    output: mpsc::Sender<Result>,
+    output: mpsc::Sender<Bytes>,
+    is_subscribed: bool,
 }
 
 impl Stdio {
@@ -21,19 +22,11 @@ impl Stdio {
         input: impl AsyncRead + Send + Unpin + 'static,
         mut output: impl AsyncWrite + Send + Unpin + 'static,
     ) -> Self {
-        let (sender, mut receiver) = mpsc::channel(10);
+        let (sender, mut receiver) = mpsc::channel::<Bytes>(10);
 
         drop(task::spawn(async move {
-            while let Some(action) = receiver.next().await {
-                match action {
-                    Result::Send(bytes) => write(&bytes, &mut output).await?,
-                    Result::Stream(mut stream) => {
-                        while let Some(bytes) = stream.next().await {
-                            write(&bytes, &mut output).await?
-                        }
-                    }
-                    Result::Accept | Result::Reject | Result::Unsupported => {}
-                }
+            while let Some(bytes) = receiver.next().await {
+                write(&bytes, &mut output).await?;
             }
 
             Ok::<(), io::Error>(())
@@ -42,28 +35,62 @@ impl Stdio {
         Self {
             input: BufReader::new(Box::new(input)),
             output: sender,
+            is_subscribed: false,
         }
     }
+
+    fn channel(&self) -> oneshot::Sender<Result> {
+        let mut output = self.output.clone();
+        let (sender, receiver) = oneshot::channel();
+
+        drop(task::spawn(async move {
+            match receiver.await {
+                Ok(Result::Send(bytes) | Result::Start(_, bytes)) => {
+                    let _ = output.send(bytes).await;
+                }
+                Ok(Result::Stream(mut stream)) => {
+                    while let Some(event) = stream.next().await {
+                        if let Some(message) = event.message {
+                            let _ = output.send(message).await;
+                        }
+                    }
+                }
+                Ok(
+                    Result::Accept
+                    | Result::Reject
+                    | Result::NotFound
+                    | Result::Unsupported
+                    | Result::Unavailable,
+                )
+                | Err(_) => {}
+            }
+        }));
+
+        sender
+    }
 }
 
 impl Transport for Stdio {
     async fn accept(&mut self) -> io::Result<Action> {
+        // The output of the server is always available to the client,
+        // so we subscribe to server messages as soon as possible
+        if !self.is_subscribed {
+            self.is_subscribed = true;
+
+            return Ok(Action::Subscribe(Session::Single, None, self.channel()));
+        }
+
         let mut line = Vec::new();
 
         if self.input.read_until(0xA, &mut line).await? == 0 {
             return Ok(Action::Quit);
         }
 
-        let mut output = self.output.clone();
-        let (sender, receiver) = oneshot::channel();
-
-        task::spawn(async move {
-            if let Ok(result) = receiver.await {
-                let _ = output.send(result).await;
-            }
-        });
-
-        Ok(Action::Handle(Bytes::from_owner(line), sender))
+        Ok(Action::Handle(
+            Session::Single,
+            Bytes::from_owner(line),
+            self.channel(),
+        ))
     }
 }
 
@@ -1,27 +1,36 @@
 use crate::mcp;
+use crate::mcp::client::{Root, elicitation, response, sampling};
+use crate::mcp::schematic::{self, Schematic};
+use crate::mcp::server::request::{CreateElicitation, CreateMessage};
 use crate::mcp::server::tool::{IntoResponse, Response};
+
+pub use crate::mcp::server::tool::Structured;
 use crate::mcp::server::{Notification, Request};
-use crate::mcp::{Map, Schema, Value};
+use crate::mcp::{Id, Map, Schema, Value, logging, progress};
 
 use futures::SinkExt;
-use futures::channel::mpsc;
+use futures::channel::{mpsc, oneshot};
+use futures::future::{AbortHandle, AbortRegistration, Abortable};
 use serde::Serialize;
+use serde::de::DeserializeOwned;
 use tokio::task;
 
 use std::collections::BTreeMap;
+use std::fmt;
 use std::io;
 use std::marker::PhantomData;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 pub struct Tool<Name = String, Description = String> {
     pub name: Name,
     pub description: Description,
     input: Schema,
     output: Option<Schema>,
-    call: Box<dyn Fn(Value) -> io::Result<mpsc::Receiver<Action>> + Send + Sync>,
+    call: Box<dyn Fn(Call) -> io::Result<mpsc::Receiver<Action>> + Send + Sync>,
 }
 
 pub enum Action {
-    Request(Request),
+    Request(Request, oneshot::Sender<io::Result<Value>>),
     Notify(Notification),
     Finish(io::Result<Response>),
 }
@@ -32,7 +41,7 @@ impl Tool<(), ()> {
     pub unsafe fn new(
         input: Schema,
         output: Option<Schema>,
-        call: impl Fn(Value) -> io::Result<mpsc::Receiver<Action>> + Send + Sync + 'static,
+        call: impl Fn(Call) -> io::Result<mpsc::Receiver<Action>> + Send + Sync + 'static,
     ) -> Self {
         Self {
             name: (),
@@ -75,13 +84,106 @@ impl Tool {
         self.output.as_ref()
     }
 
-    pub fn call(&self, json: Value) -> io::Result<mpsc::Receiver<Action>> {
-        (self.call)(json)
+    pub fn call(&self, call: Call) -> io::Result<mpsc::Receiver<Action>> {
+        (self.call)(call)
+    }
+}
+
+/// The set of tools of a server, which may change while it is running.
+#[derive(Default)]
+pub(crate) struct Registry {
+    tools: RwLock<BTreeMap<String, Arc<Tool>>>,
+}
+
+impl Registry {
+    pub fn get(&self, name: &str) -> Option<Arc<Tool>> {
+        self.read().get(name).cloned()
+    }
+
+    pub fn list(&self) -> Vec<Arc<Tool>> {
+        self.read().values().cloned().collect()
+    }
+
+    pub fn insert(&self, tool: Tool) {
+        let _ = self.write().insert(tool.name.clone(), Arc::new(tool));
+    }
+
+    pub fn remove(&self, name: &str) -> bool {
+        self.write().remove(name).is_some()
+    }
+
+    pub fn replace(&self, tools: impl IntoIterator<Item = Tool>) {
+        *self.write() = tools
+            .into_iter()
+            .map(|tool| (tool.name.clone(), Arc::new(tool)))
+            .collect();
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, BTreeMap<String, Arc<Tool>>> {
+        self.tools.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, BTreeMap<String, Arc<Tool>>> {
+        self.tools.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.read().keys()).finish()
+    }
+}
+
+/// A request to run a [`Tool`].
+#[derive(Debug)]
+pub struct Call {
+    pub arguments: Value,
+    metadata: Metadata,
+}
+
+#[derive(Debug)]
+struct Metadata {
+    id: Id,
+    progress_token: Option<progress::Token>,
+    cancellation: AbortRegistration,
+}
+
+impl Call {
+    pub(crate) fn new(
+        id: Id,
+        arguments: Value,
+        progress_token: Option<progress::Token>,
+    ) -> (Self, AbortHandle) {
+        let (handle, registration) = AbortHandle::new_pair();
+
+        (
+            Self {
+                arguments,
+                metadata: Metadata {
+                    id,
+                    progress_token,
+                    cancellation: registration,
+                },
+            },
+            handle,
+        )
+    }
+
+    pub fn id(&self) -> Id {
+        self.metadata.id
+    }
+
+    pub fn progress_token(&self) -> Option<&progress::Token> {
+        self.metadata.progress_token.as_ref()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.metadata.cancellation.handle().is_aborted()
     }
 }
 
-pub fn tool<A, O, F>(
-    f: impl Fn(A) -> F + Send + Sync + 'static,
+pub fn tool<A, M, O, F>(
+    f: impl Function<(A,), M, Future = F> + Send + Sync + 'static,
     a: impl Argument<A> + Send + Sync + 'static,
 ) -> Tool<(), ()>
 where
@@ -95,24 +197,24 @@ where
         required: Vec::from_iter([required(&a)].into_iter().flatten()),
     };
 
-    let call = move |json| {
-        let mut object = object(json)?;
+    let call = move |call: Call| {
+        let mut object = object(call.arguments)?;
         let a = deserialize(&a, &mut object)?;
 
-        Ok(spawn(f(a)))
+        spawn(call.metadata, |context| Ok(f.call(context, (a,))))
     };
 
     Tool {
         name: (),
         description: (),
         input,
-        output: None, // TODO
+        output: O::output_schema(),
         call: Box::new(call),
     }
 }
 
-pub fn tool_2<A, B, O, F>(
-    f: impl Fn(A, B) -> F + Send + Sync + 'static,
+pub fn tool_2<A, B, M, O, F>(
+    f: impl Function<(A, B), M, Future = F> + Send + Sync + 'static,
     a: impl Argument<A> + Send + Sync + 'static,
     b: impl Argument<B> + Send + Sync + 'static,
 ) -> Tool<(), ()>
@@ -127,32 +229,164 @@ where
         required: Vec::from_iter([required(&a), required(&b)].into_iter().flatten()),
     };
 
-    let call = move |json| {
-        let mut object = object(json)?;
+    let call = move |call: Call| {
+        let mut object = object(call.arguments)?;
         let a = deserialize(&a, &mut object)?;
         let b = deserialize(&b, &mut object)?;
 
-        Ok(spawn(f(a, b)))
+        spawn(call.metadata, |context| Ok(f.call(context, (a, b))))
     };
 
     Tool {
         name: (),
         description: (),
         input,
-        output: None, // TODO
+        output: O::output_schema(),
+        call: Box::new(call),
+    }
+}
+
+/// Creates a [`Tool`] that takes all of its arguments as a single input.
+///
+/// The input schema of the tool is the [`Schematic`] of the input type,
+/// which must be an object.
+pub fn typed<I, M, O, F>(
+    f: impl Function<(I,), M, Future = F> + Send + Sync + 'static,
+) -> Tool<(), ()>
+where
+    I: Schematic + DeserializeOwned + Send + 'static,
+    O: IntoResponse,
+    O::Content: Serialize + Send,
+    F: Future<Output = O> + Send + 'static,
+{
+    let call = move |call: Call| {
+        let input = mcp::from_value(Value::Object(object(call.arguments)?))?;
+
+        spawn(call.metadata, |context| Ok(f.call(context, (input,))))
+    };
+
+    Tool {
+        name: (),
+        description: (),
+        input: I::schema(),
+        output: O::output_schema(),
+        call: Box::new(call),
+    }
+}
+
+/// Creates a [`Tool`] with any number of arguments.
+///
+/// Every argument must be added to the [`Signature`] before being taken
+/// from the [`Arguments`] of a call.
+pub fn from_signature<O, F>(
+    signature: Signature,
+    f: impl Fn(Context, Arguments) -> io::Result<F> + Send + Sync + 'static,
+) -> Tool<(), ()>
+where
+    O: IntoResponse,
+    O::Content: Serialize + Send,
+    F: Future<Output = O> + Send + 'static,
+{
+    let call = move |call: Call| {
+        let arguments = Arguments(object(call.arguments)?);
+
+        spawn(call.metadata, |context| f(context, arguments))
+    };
+
+    Tool {
+        name: (),
+        description: (),
+        input: signature.schema(),
+        output: O::output_schema(),
         call: Box::new(call),
     }
 }
 
-fn spawn<O>(execution: impl Future<Output = O> + Send + 'static) -> mpsc::Receiver<Action>
+/// The input schema of a tool, built argument by argument.
+#[derive(Debug, Clone, Default)]
+pub struct Signature {
+    properties: BTreeMap<String, Schema>,
+    required: Vec<String>,
+}
+
+impl Signature {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an argument to the [`Signature`] and returns it back.
+    pub fn argument<T, A>(&mut self, argument: A) -> A
+    where
+        A: Argument<T>,
+    {
+        let (name, schema) = property(&argument);
+
+        if let Some(name) = required(&argument) {
+            self.required.push(name);
+        }
+
+        let _ = self.properties.insert(name, schema);
+
+        argument
+    }
+
+    fn schema(self) -> Schema {
+        Schema::Object {
+            description: None,
+            properties: self.properties,
+            required: self.required,
+        }
+    }
+}
+
+/// The arguments of a tool call.
+#[derive(Debug, Clone)]
+pub struct Arguments(Map<String, Value>);
+
+impl Arguments {
+    pub fn take<T>(&mut self, argument: &impl Argument<T>) -> io::Result<T> {
+        deserialize(argument, &mut self.0)
+    }
+}
+
+/// A type that can be used as the argument of a tool.
+pub trait Parameter: Sized {
+    fn argument(name: &str, description: &str) -> Box<dyn Argument<Self> + Send + Sync>;
+}
+
+impl<T> Parameter for T
+where
+    T: Schematic + DeserializeOwned + 'static,
+{
+    fn argument(name: &str, description: &str) -> Box<dyn Argument<Self> + Send + Sync> {
+        Box::new(argument(name.to_owned(), description.to_owned()))
+    }
+}
+
+fn spawn<O, F>(
+    metadata: Metadata,
+    execute: impl FnOnce(Context) -> io::Result<F>,
+) -> io::Result<mpsc::Receiver<Action>>
 where
     O: IntoResponse,
     O::Content: Serialize + Send,
+    F: Future<Output = O> + Send + 'static,
 {
     let (mut sender, receiver) = mpsc::channel(1);
 
-    task::spawn(async move {
-        let output = execution.await;
+    let context = Context {
+        id: metadata.id,
+        progress_token: metadata.progress_token,
+        cancellation: metadata.cancellation.handle(),
+        actions: sender.clone(),
+    };
+
+    let execution = Abortable::new(execute(context)?, metadata.cancellation);
+
+    drop(task::spawn(async move {
+        let Ok(output) = execution.await else {
+            return;
+        };
 
         let result = output
             .into_outcome()
@@ -161,9 +395,187 @@ where
             .map_err(io::Error::from);
 
         let _ = sender.send(Action::Finish(result)).await;
-    });
+    }));
+
+    Ok(receiver)
+}
+
+/// The context of a tool call.
+///
+/// A tool function can take a [`Context`] as its first parameter in order
+/// to interact with the client while it is running.
+#[derive(Debug, Clone)]
+pub struct Context {
+    id: Id,
+    progress_token: Option<progress::Token>,
+    cancellation: AbortHandle,
+    actions: mpsc::Sender<Action>,
+}
+
+impl Context {
+    /// Returns the id of the request that started the tool call.
+    pub fn request_id(&self) -> Id {
+        self.id
+    }
 
-    receiver
+    /// Returns the token the client attached to receive progress updates, if any.
+    pub fn progress_token(&self) -> Option<&progress::Token> {
+        self.progress_token.as_ref()
+    }
+
+    /// Returns `true` if the tool call has been cancelled.
+    ///
+    /// A cancelled tool call is stopped at its next `.await` point and
+    /// its response is discarded.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_aborted()
+    }
+
+    /// Reports the progress of the tool call to the client.
+    ///
+    /// Nothing is sent if the client did not ask for progress updates.
+    pub async fn progress(
+        &self,
+        progress: f64,
+        total: Option<f64>,
+        message: Option<&str>,
+    ) -> io::Result<()> {
+        let Some(progress_token) = self.progress_token.clone() else {
+            return Ok(());
+        };
+
+        self.notify(progress::Progress {
+            progress_token,
+            progress,
+            total,
+            message: message.map(str::to_owned),
+        })
+        .await
+    }
+
+    /// Sends a log message to the client.
+    pub async fn log(&self, level: logging::Level, data: impl Serialize) -> io::Result<()> {
+        self.notify(logging::Message {
+            level,
+            logger: None,
+            data: mcp::to_value(data)?,
+        })
+        .await
+    }
+
+    /// Asks the client to sample a message from its language model.
+    pub async fn sample(&self, request: CreateMessage) -> io::Result<sampling::Response> {
+        mcp::from_value(self.request(request).await?)
+    }
+
+    /// Asks the client to request some input from the user.
+    ///
+    /// The `schema` must be an object with primitive properties.
+    pub async fn elicit<T>(
+        &self,
+        message: impl AsRef<str>,
+        schema: Schema,
+    ) -> io::Result<elicitation::Response<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let request = CreateElicitation {
+            message: message.as_ref().to_owned(),
+            requested_schema: elicitation::Schema::try_from(schema)?,
+        };
+
+        mcp::from_value(self.request(request).await?)
+    }
+
+    /// Returns the roots exposed by the client.
+    ///
+    /// The roots are cached by the session until the client notifies
+    /// a change.
+    pub async fn roots(&self) -> io::Result<Vec<Root>> {
+        let response::RootsList { roots } =
+            mcp::from_value(self.request(Request::RootsList).await?)?;
+
+        Ok(roots)
+    }
+
+    async fn notify(&self, notification: impl Into<Notification>) -> io::Result<()> {
+        self.actions
+            .clone()
+            .send(Action::Notify(notification.into()))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "tool call has finished"))
+    }
+
+    async fn request(&self, request: impl Into<Request>) -> io::Result<Value> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.actions
+            .clone()
+            .send(Action::Request(request.into(), sender))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "tool call has finished"))?;
+
+        receiver.await.map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "request was dropped before receiving a response",
+            )
+        })?
+    }
+}
+
+pub trait Function<A, M> {
+    type Future: Future;
+
+    fn call(&self, context: Context, arguments: A) -> Self::Future;
+}
+
+impl<T, F, A> Function<(A,), ()> for T
+where
+    T: Fn(A) -> F,
+    F: Future,
+{
+    type Future = F;
+
+    fn call(&self, _context: Context, (a,): (A,)) -> F {
+        self(a)
+    }
+}
+
+impl<T, F, A> Function<(A,), Context> for T
+where
+    T: Fn(Context, A) -> F,
+    F: Future,
+{
+    type Future = F;
+
+    fn call(&self, context: Context, (a,): (A,)) -> F {
+        self(context, a)
+    }
+}
+
+impl<T, F, A, B> Function<(A, B), ()> for T
+where
+    T: Fn(A, B) -> F,
+    F: Future,
+{
+    type Future = F;
+
+    fn call(&self, _context: Context, (a, b): (A, B)) -> F {
+        self(a, b)
+    }
+}
+
+impl<T, F, A, B> Function<(A, B), Context> for T
+where
+    T: Fn(Context, A, B) -> F,
+    F: Future,
+{
+    type Future = F;
+
+    fn call(&self, context: Context, (a, b): (A, B)) -> F {
+        self(context, a, b)
+    }
 }
 
 pub trait Argument<T> {
@@ -178,6 +590,24 @@ pub trait Argument<T> {
     }
 }
 
+impl<T> Argument<T> for Box<dyn Argument<T> + Send + Sync> {
+    fn name(&self) -> &str {
+        self.as_ref().name()
+    }
+
+    fn schema(&self) -> Schema {
+        self.as_ref().schema()
+    }
+
+    fn deserialize(&self, json: Value) -> io::Result<T> {
+        self.as_ref().deserialize(json)
+    }
+
+    fn is_required(&self) -> bool {
+        self.as_ref().is_required()
+    }
+}
+
 pub fn string(name: impl AsRef<str>, description: impl AsRef<str>) -> impl Argument<String> {
     NamedArg::new(name, description)
 }
@@ -194,6 +624,46 @@ pub fn bool(name: impl AsRef<str>, description: impl AsRef<str>) -> impl Argumen
     NamedArg::new(name, description)
 }
 
+/// Creates an [`Argument`] of any [`Schematic`] type.
+pub fn argument<T>(name: impl AsRef<str>, description: impl AsRef<str>) -> impl Argument<T>
+where
+    T: Schematic + DeserializeOwned,
+{
+    struct Typed<T> {
+        argument: NamedArg,
+        _type: PhantomData<fn() -> T>,
+    }
+
+    impl<T> Argument<T> for Typed<T>
+    where
+        T: Schematic + DeserializeOwned,
+    {
+        fn name(&self) -> &str {
+            &self.argument.name
+        }
+
+        fn schema(&self) -> Schema {
+            match &self.argument.description {
+                Some(description) => schematic::describe(T::schema(), description),
+                None => T::schema(),
+            }
+        }
+
+        fn deserialize(&self, json: Value) -> io::Result<T> {
+            mcp::from_value(json)
+        }
+
+        fn is_required(&self) -> bool {
+            !T::is_optional()
+        }
+    }
+
+    Typed {
+        argument: NamedArg::new(name, description),
+        _type: PhantomData,
+    }
+}
+
 pub fn optional<T>(argument: impl Argument<T>) -> impl Argument<Option<T>> {
     struct Optional<A, T> {
         argument: A,
@@ -233,14 +703,16 @@ pub fn optional<T>(argument: impl Argument<T>) -> impl Argument<Option<T>> {
 
 struct NamedArg {
     name: String,
-    description: String,
+    description: Option<String>,
 }
 
 impl NamedArg {
     fn new(name: impl AsRef<str>, description: impl AsRef<str>) -> Self {
+        let description = description.as_ref();
+
         Self {
             name: name.as_ref().to_owned(),
-            description: description.as_ref().to_owned(),
+            description: (!description.is_empty()).then(|| description.to_owned()),
         }
     }
 }
@@ -252,7 +724,7 @@ impl Argument<String> for NamedArg {
 
     fn schema(&self) -> Schema {
         Schema::String {
-            description: Some(self.description.clone()),
+            description: self.description.clone(),
         }
     }
 
@@ -268,7 +740,7 @@ impl Argument<u32> for NamedArg {
 
     fn schema(&self) -> Schema {
         Schema::Integer {
-            description: Some(self.description.clone()),
+            description: self.description.clone(),
         }
     }
 
@@ -284,7 +756,7 @@ impl Argument<f32> for NamedArg {
 
     fn schema(&self) -> Schema {
         Schema::Number {
-            description: Some(self.description.clone()),
+            description: self.description.clone(),
         }
     }
 
@@ -300,7 +772,7 @@ impl Argument<bool> for NamedArg {
 
     fn schema(&self) -> Schema {
         Schema::Boolean {
-            description: Some(self.description.clone()),
+            description: self.description.clone(),
         }
     }
 
@@ -323,4 +795,52 @@ fn object(json: Value) -> io::Result<Map<String, Value>> {
 
 fn deserialize<T>(arg: &impl Argument<T>, object: &mut Map<String, Value>) -> io::Result<T> {
     arg.deserialize(object.remove(arg.name()).unwrap_or(Value::Null))
+        .map_err(|error| io::Error::new(error.kind(), format!("{:?}: {error}", arg.name())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::json;
+
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Unit {
+        Celsius,
+        Fahrenheit,
+    }
+
+    impl Schematic for Unit {
+        fn schema() -> Schema {
+            Schema::String { description: None }
+        }
+    }
+
+    fn arguments(value: Value) -> Map<String, Value> {
+        object(value).expect("arguments must be an object")
+    }
+
+    #[test]
+    fn typed_arguments() {
+        let unit = argument::<Unit>("unit", "The unit");
+        let mut object = arguments(json!({ "unit": "celsius" }));
+
+        assert_eq!(deserialize(&unit, &mut object).ok(), Some(Unit::Celsius));
+    }
+
+    #[test]
+    fn invalid_arguments_are_named() {
+        let unit = argument::<Unit>("unit", "The unit");
+        let mut object = arguments(json!({ "unit": "kelvin" }));
+
+        let error = deserialize(&unit, &mut object).expect_err("argument must be invalid");
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "\"unit\": unknown variant `kelvin`, expected `celsius` or `fahrenheit`"
+        );
+    }
 }
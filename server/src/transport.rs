@@ -1,4 +1,4 @@
-use crate::mcp::Bytes;
+use crate::mcp::{Bytes, Version};
 
 use futures::channel::mpsc;
 use futures::channel::oneshot;
@@ -10,17 +10,60 @@ pub trait Transport {
 }
 
 pub enum Action {
-    Subscribe(Channel),
-    Handle(Bytes, Channel),
+    /// Opens the stream of server messages of a session, resuming the
+    /// stream of the given event id, if any.
+    Subscribe(Session, Option<u64>, Channel),
+    Handle(Session, Bytes, Channel),
+    /// Ends the session with the given id.
+    End(String, Channel),
     Quit,
 }
 
+/// The session an [`Action`] belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Session {
+    /// The transport serves a single client during its whole lifetime.
+    Single,
+    /// The client has not started a session yet.
+    None,
+    /// The client has started the session with the given id, using the
+    /// given protocol version, if specified.
+    Id(String, Option<Version>),
+}
+
+impl Session {
+    /// Returns the protocol version the client claims to be using, if any.
+    pub fn version(&self) -> Option<Version> {
+        match self {
+            Session::Single | Session::None => None,
+            Session::Id(_, version) => *version,
+        }
+    }
+}
+
 pub type Channel = oneshot::Sender<Result>;
 
 pub enum Result {
     Accept,
     Reject,
     Send(Bytes),
-    Stream(mpsc::Receiver<Bytes>),
+    Stream(mpsc::Receiver<Event>),
+    /// Sends the response that started the session with the given id.
+    Start(String, Bytes),
+    NotFound,
     Unsupported,
+    /// The server cannot start any more sessions for now.
+    Unavailable,
+}
+
+/// A message sent through a stream.
+#[derive(Debug, Clone)]
+pub struct Event {
+    /// The id of the event, unique within its session.
+    pub id: u64,
+    /// The message of the event, if any.
+    ///
+    /// Streams start with an event without a message, so clients can
+    /// resume them even before receiving any messages.
+    pub message: Option<Bytes>,
 }
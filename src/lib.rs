@@ -11,3 +11,9 @@ pub use techne_server as server;
 
 #[cfg(feature = "server")]
 pub use server::Server;
+
+#[cfg(feature = "macros")]
+pub use techne_macros::{Schematic, tool};
+
+#[cfg(feature = "codegen")]
+pub use techne_codegen as codegen;